@@ -0,0 +1,146 @@
+use crate::eval::Evaluator;
+use crate::{
+    BigInt, CalcError, CalcResult, Context, EvalOptions, LiteralBase, Precision, Signedness, Span,
+    Value,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal {
+    pub base: LiteralBase,
+    pub text: String,
+    pub value: BigInt,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            UnaryOp::Neg => "-",
+            UnaryOp::Not => "~",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    LogicalShr,
+    Rotl,
+    Rotr,
+}
+
+impl BinaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Pow => "**",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+            BinaryOp::LogicalShr => ">>>",
+            BinaryOp::Rotl => "rotl",
+            BinaryOp::Rotr => "rotr",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Literal(Literal),
+    Variable {
+        name: String,
+        span: Span,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+        span: Span,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        span: Span,
+    },
+    Paren {
+        inner: Box<Expr>,
+        span: Span,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialAst {
+    // 読み飛ばしたトークンで区切られた断片ごとの構文木（左から順）
+    pub exprs: Vec<Expr>,
+    pub diagnostics: Vec<CalcError>,
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Literal(literal) => literal.span,
+            Expr::Variable { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::Unary { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Paren { span, .. } => *span,
+        }
+    }
+
+    // 左結合の連鎖 `a op b op c` を先頭の被演算子と (節, 演算子, 右辺) の並びにほどく。
+    // 連鎖は入れ子の深さに数えず長くなりうるので、左辺を再帰でたどらずにこれを使う
+    pub(crate) fn left_chain(&self) -> (&Expr, Vec<(&Expr, BinaryOp, &Expr)>) {
+        let mut links = Vec::new();
+        let mut first = self;
+        while let Expr::Binary { op, lhs, rhs, .. } = first {
+            links.push((first, *op, &**rhs));
+            first = lhs;
+        }
+        links.reverse();
+        (first, links)
+    }
+
+    pub fn eval(&self, options: &EvalOptions) -> CalcResult<Value> {
+        self.eval_in(&Context::with_options(*options))
+    }
+
+    pub fn eval_in(&self, context: &Context) -> CalcResult<Value> {
+        let options = context.options();
+        match (options.precision, options.signedness) {
+            (Precision::Arbitrary, _) => Evaluator::<BigInt>::new(context).eval_value(self),
+            (Precision::Fixed, Signedness::Signed) => {
+                Evaluator::<i128>::new(context).eval_value(self)
+            }
+            (Precision::Fixed, Signedness::Unsigned) => {
+                Evaluator::<u128>::new(context).eval_value(self)
+            }
+        }
+    }
+}
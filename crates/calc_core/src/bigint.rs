@@ -0,0 +1,496 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+const LIMB_BITS: u32 = 32;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn bit_len(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => {
+                self.limbs.len() as u64 * u64::from(LIMB_BITS) - u64::from(top.leading_zeros())
+            }
+        }
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.negative {
+            None
+        } else {
+            self.magnitude_u128()
+        }
+    }
+
+    pub fn to_str_radix(&self, radix: u32, uppercase: bool) -> String {
+        let digits = self.magnitude_digits(radix, uppercase);
+        if self.negative {
+            format!("-{digits}")
+        } else {
+            digits
+        }
+    }
+
+    pub(crate) fn magnitude_digits(&self, radix: u32, uppercase: bool) -> String {
+        assert!((2..=36).contains(&radix));
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        let mut chunk = radix;
+        let mut chunk_digits = 1;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            chunk_digits += 1;
+        }
+
+        let mut limbs = self.limbs.clone();
+        let mut out = Vec::new();
+        while !limbs.is_empty() {
+            let mut rem = divrem_small(&mut limbs, chunk);
+            for _ in 0..chunk_digits {
+                let digit = std::char::from_digit(rem % radix, radix).unwrap_or('?');
+                out.push(if uppercase {
+                    digit.to_ascii_uppercase()
+                } else {
+                    digit
+                });
+                rem /= radix;
+                if limbs.is_empty() && rem == 0 {
+                    break;
+                }
+            }
+        }
+        while out.len() > 1 && out.last() == Some(&'0') {
+            out.pop();
+        }
+        out.iter().rev().collect()
+    }
+
+    pub(crate) fn ones(bits: u64) -> Self {
+        let full = (bits / u64::from(LIMB_BITS)) as usize;
+        let rest = (bits % u64::from(LIMB_BITS)) as u32;
+        let mut limbs = vec![u32::MAX; full];
+        if rest > 0 {
+            limbs.push(u32::MAX >> (LIMB_BITS - rest));
+        }
+        BigInt::from_parts(false, limbs)
+    }
+
+    pub(crate) fn mul_small_add(&mut self, mul: u32, add: u32) {
+        debug_assert!(!self.negative);
+        let mut carry = u64::from(add);
+        for limb in &mut self.limbs {
+            let value = u64::from(*limb) * u64::from(mul) + carry;
+            *limb = value as u32;
+            carry = value >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.normalize();
+    }
+
+    pub(crate) fn low_u128(&self) -> u128 {
+        let magnitude = self
+            .limbs
+            .iter()
+            .take(4)
+            .rev()
+            .fold(0u128, |acc, limb| (acc << LIMB_BITS) | u128::from(*limb));
+        if self.negative {
+            magnitude.wrapping_neg()
+        } else {
+            magnitude
+        }
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+
+    pub(crate) fn abs(&self) -> Self {
+        BigInt::from_parts(false, self.limbs.clone())
+    }
+
+    pub(crate) fn add(&self, rhs: &Self) -> Self {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, add_mag(&self.limbs, &rhs.limbs));
+        }
+        match cmp_mag(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigInt::from_parts(rhs.negative, sub_mag(&rhs.limbs, &self.limbs)),
+            _ => BigInt::from_parts(self.negative, sub_mag(&self.limbs, &rhs.limbs)),
+        }
+    }
+
+    pub(crate) fn sub(&self, rhs: &Self) -> Self {
+        self.add(&rhs.neg())
+    }
+
+    pub(crate) fn mul(&self, rhs: &Self) -> Self {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_mag(&self.limbs, &rhs.limbs),
+        )
+    }
+
+    pub(crate) fn div_rem_euclid(&self, rhs: &Self) -> Option<(Self, Self)> {
+        if rhs.is_zero() {
+            return None;
+        }
+        let (quot, rem) = divrem_mag(&self.limbs, &rhs.limbs);
+        let mut quot = BigInt::from_parts(self.negative != rhs.negative, quot);
+        let mut rem = BigInt::from_parts(self.negative, rem);
+        if rem.negative {
+            rem = rem.add(&rhs.abs());
+            let one = BigInt::from(1u32);
+            quot = if rhs.negative {
+                quot.add(&one)
+            } else {
+                quot.sub(&one)
+            };
+        }
+        Some((quot, rem))
+    }
+
+    pub(crate) fn pow(&self, mut exponent: u32, max_bits: u64) -> Option<Self> {
+        let mut result = BigInt::from(1u32);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+                if result.bit_len() > max_bits {
+                    return None;
+                }
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+                if base.bit_len() > max_bits {
+                    return None;
+                }
+            }
+        }
+        Some(result)
+    }
+
+    pub(crate) fn shl(&self, amount: u32) -> Self {
+        BigInt::from_parts(self.negative, shl_mag(&self.limbs, amount))
+    }
+
+    pub(crate) fn shr(&self, amount: u32) -> Self {
+        if !self.negative {
+            return BigInt::from_parts(false, shr_mag(&self.limbs, amount));
+        }
+        // 負の値は -((|x| - 1) >> n) - 1 で床方向に丸める
+        let one = BigInt::from(1u32);
+        let reduced = sub_mag(&self.limbs, &one.limbs);
+        BigInt::from_parts(false, shr_mag(&reduced, amount))
+            .add(&one)
+            .neg()
+    }
+
+    pub(crate) fn not(&self) -> Self {
+        self.add(&BigInt::from(1u32)).neg()
+    }
+
+    pub(crate) fn bitwise(&self, rhs: &Self, op: impl Fn(u32, u32) -> u32) -> Self {
+        let len = self.limbs.len().max(rhs.limbs.len()) + 1;
+        let lhs = self.to_twos(len);
+        let rhs = rhs.to_twos(len);
+        let limbs = lhs.iter().zip(&rhs).map(|(a, b)| op(*a, *b)).collect();
+        BigInt::from_twos(limbs)
+    }
+
+    fn to_twos(&self, len: usize) -> Vec<u32> {
+        let mut limbs = self.limbs.clone();
+        limbs.resize(len, 0);
+        if self.negative {
+            negate_limbs(&mut limbs);
+        }
+        limbs
+    }
+
+    fn from_twos(mut limbs: Vec<u32>) -> Self {
+        let negative = limbs.last().is_some_and(|top| top >> (LIMB_BITS - 1) == 1);
+        if negative {
+            negate_limbs(&mut limbs);
+        }
+        BigInt::from_parts(negative, limbs)
+    }
+
+    fn magnitude_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, limb| (acc << LIMB_BITS) | u128::from(*limb)),
+        )
+    }
+
+    fn from_parts(negative: bool, limbs: Vec<u32>) -> Self {
+        let mut value = BigInt { negative, limbs };
+        value.normalize();
+        value
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        if self.limbs.is_empty() {
+            self.negative = false;
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_str_radix(10, false))
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(value: u128) -> Self {
+        let limbs = (0..4).map(|i| (value >> (i * LIMB_BITS)) as u32).collect();
+        BigInt::from_parts(false, limbs)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> Self {
+        let magnitude = BigInt::from(value.unsigned_abs());
+        BigInt::from_parts(value < 0, magnitude.limbs)
+    }
+}
+
+impl From<u32> for BigInt {
+    fn from(value: u32) -> Self {
+        BigInt::from(u128::from(value))
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, limb) in long.iter().enumerate() {
+        let sum = u64::from(*limb) + u64::from(short.get(i).copied().unwrap_or(0)) + carry;
+        out.push(sum as u32);
+        carry = sum >> LIMB_BITS;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// a >= b が前提
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, limb) in a.iter().enumerate() {
+        let mut diff = i64::from(*limb) - i64::from(b.get(i).copied().unwrap_or(0)) - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << LIMB_BITS;
+            borrow = 1;
+        }
+        out.push(diff as u32);
+    }
+    out
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let value = u64::from(*x) * u64::from(*y) + u64::from(out[i + j]) + carry;
+            out[i + j] = value as u32;
+            carry = value >> LIMB_BITS;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+fn divrem_small(limbs: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in limbs.iter_mut().rev() {
+        let value = (rem << LIMB_BITS) | u64::from(*limb);
+        *limb = (value / u64::from(divisor)) as u32;
+        rem = value % u64::from(divisor);
+    }
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    rem as u32
+}
+
+// Knuth の Algorithm D
+fn divrem_mag(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(u, v) == Ordering::Less {
+        return (Vec::new(), u.to_vec());
+    }
+    if v.len() == 1 {
+        let mut quot = u.to_vec();
+        let rem = divrem_small(&mut quot, v[0]);
+        return (quot, vec![rem]);
+    }
+
+    let base = 1u64 << LIMB_BITS;
+    let n = v.len();
+    let m = u.len() - n;
+    let shift = v[n - 1].leading_zeros();
+    let vn = shl_mag(v, shift);
+    let mut un = shl_mag(u, shift);
+    un.resize(u.len() + 1, 0);
+
+    let mut quot = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        let num = (u64::from(un[j + n]) << LIMB_BITS) | u64::from(un[j + n - 1]);
+        let mut qhat = num / u64::from(vn[n - 1]);
+        let mut rhat = num % u64::from(vn[n - 1]);
+        while qhat >= base
+            || u128::from(qhat) * u128::from(vn[n - 2])
+                > (u128::from(rhat) << LIMB_BITS) | u128::from(un[j + n - 2])
+        {
+            qhat -= 1;
+            rhat += u64::from(vn[n - 1]);
+            if rhat >= base {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let product = qhat * u64::from(vn[i]);
+            let diff = i64::from(un[i + j]) - borrow - (product & 0xFFFF_FFFF) as i64;
+            un[i + j] = diff as u32;
+            borrow = (product >> LIMB_BITS) as i64 - (diff >> LIMB_BITS);
+        }
+        let diff = i64::from(un[j + n]) - borrow;
+        un[j + n] = diff as u32;
+
+        if diff < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u64::from(un[i + j]) + u64::from(vn[i]) + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> LIMB_BITS;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+        quot[j] = qhat as u32;
+    }
+
+    un.truncate(n);
+    (quot, shr_mag(&un, shift))
+}
+
+fn shl_mag(limbs: &[u32], amount: u32) -> Vec<u32> {
+    if limbs.is_empty() {
+        return Vec::new();
+    }
+    let words = (amount / LIMB_BITS) as usize;
+    let bits = amount % LIMB_BITS;
+    let mut out = vec![0u32; words];
+    if bits == 0 {
+        out.extend_from_slice(limbs);
+        return out;
+    }
+    let mut carry = 0u32;
+    for limb in limbs {
+        out.push((limb << bits) | carry);
+        carry = limb >> (LIMB_BITS - bits);
+    }
+    if carry > 0 {
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_mag(limbs: &[u32], amount: u32) -> Vec<u32> {
+    let words = (amount / LIMB_BITS) as usize;
+    if words >= limbs.len() {
+        return Vec::new();
+    }
+    let bits = amount % LIMB_BITS;
+    let rest = &limbs[words..];
+    if bits == 0 {
+        return rest.to_vec();
+    }
+    rest.iter()
+        .enumerate()
+        .map(|(i, limb)| {
+            let high = rest.get(i + 1).map_or(0, |next| next << (LIMB_BITS - bits));
+            (limb >> bits) | high
+        })
+        .collect()
+}
+
+fn negate_limbs(limbs: &mut [u32]) {
+    let mut carry = true;
+    for limb in limbs {
+        let (value, overflow) = (!*limb).overflowing_add(u32::from(carry));
+        *limb = value;
+        carry = overflow;
+    }
+}
@@ -0,0 +1,114 @@
+use crate::int::{check_range, Int};
+use crate::{ErrorKind, Operation, Width};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuiltinOp {
+    Popcount,
+    Clz,
+    Ctz,
+    Bswap,
+    Bitrev,
+    Abs,
+    Min,
+    Max,
+}
+
+pub(crate) struct Builtin {
+    pub(crate) name: &'static str,
+    pub(crate) arity: usize,
+    op: BuiltinOp,
+}
+
+impl Builtin {
+    pub(crate) fn call<T: Int>(&self, args: &[T], width: Width) -> Result<T, ErrorKind> {
+        if args.len() != self.arity {
+            return Err(ErrorKind::ArityMismatch {
+                name: self.name.to_string(),
+                expected: self.arity,
+                found: args.len(),
+            });
+        }
+        check_range(self.eval(args, width)?, width).ok_or_else(|| self.overflow(args))
+    }
+
+    fn overflow<T: Int>(&self, args: &[T]) -> ErrorKind {
+        ErrorKind::Overflow {
+            op: Operation::Call(self.name.to_string()),
+            operands: args.iter().map(Int::to_value).collect(),
+        }
+    }
+
+    fn eval<T: Int>(&self, args: &[T], width: Width) -> Result<T, ErrorKind> {
+        let unused = 128 - width.bits();
+        let bits = || {
+            args[0]
+                .to_bits(width)
+                .ok_or_else(|| ErrorKind::RequiresFixedWidth(self.name.to_string()))
+        };
+        let result = match self.op {
+            BuiltinOp::Popcount => T::from_u32(bits()?.count_ones()),
+            BuiltinOp::Clz => T::from_u32(bits()?.leading_zeros() - unused),
+            BuiltinOp::Ctz => {
+                let bits = bits()?;
+                if bits == 0 {
+                    T::from_u32(width.bits())
+                } else {
+                    T::from_u32(bits.trailing_zeros())
+                }
+            }
+            BuiltinOp::Bswap => T::from_bits(bits()?.swap_bytes() >> unused, width),
+            BuiltinOp::Bitrev => T::from_bits(bits()?.reverse_bits() >> unused, width),
+            BuiltinOp::Abs => args[0].checked_abs().ok_or_else(|| self.overflow(args))?,
+            BuiltinOp::Min => args[0].clone().min(args[1].clone()),
+            BuiltinOp::Max => args[0].clone().max(args[1].clone()),
+        };
+        Ok(result)
+    }
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "popcount",
+        arity: 1,
+        op: BuiltinOp::Popcount,
+    },
+    Builtin {
+        name: "clz",
+        arity: 1,
+        op: BuiltinOp::Clz,
+    },
+    Builtin {
+        name: "ctz",
+        arity: 1,
+        op: BuiltinOp::Ctz,
+    },
+    Builtin {
+        name: "bswap",
+        arity: 1,
+        op: BuiltinOp::Bswap,
+    },
+    Builtin {
+        name: "bitrev",
+        arity: 1,
+        op: BuiltinOp::Bitrev,
+    },
+    Builtin {
+        name: "abs",
+        arity: 1,
+        op: BuiltinOp::Abs,
+    },
+    Builtin {
+        name: "min",
+        arity: 2,
+        op: BuiltinOp::Min,
+    },
+    Builtin {
+        name: "max",
+        arity: 2,
+        op: BuiltinOp::Max,
+    },
+];
+
+pub(crate) fn lookup(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
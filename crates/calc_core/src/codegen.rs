@@ -0,0 +1,1499 @@
+use std::collections::BTreeSet;
+
+use crate::ast::{BinaryOp, Expr, Literal, UnaryOp};
+use crate::{
+    builtins, BigInt, CalcError, CalcResult, ErrorKind, EvalOptions, LiteralBase, OverflowPolicy,
+    Precision, Signedness,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    C,
+    Rust,
+    Python,
+    Verilog,
+}
+
+impl Language {
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::C => "C",
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::Verilog => "Verilog",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GeneratedCode {
+    pub helpers: String,
+    pub expr: String,
+}
+
+// 補助関数（この順に出力する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Helper {
+    Prelude,
+    InRange,
+    FromBits,
+    Fit,
+    Var,
+    Amount,
+    Add,
+    Sub,
+    Mul,
+    Neg,
+    Div,
+    Rem,
+    Pow,
+    Shl,
+    Shr,
+    LogicalShr,
+    Rotl,
+    Rotr,
+    Popcount,
+    Clz,
+    Ctz,
+    Bswap,
+    Bitrev,
+    Abs,
+    Min,
+    Max,
+}
+
+impl Helper {
+    fn name(self) -> &'static str {
+        match self {
+            Helper::Prelude => "prelude",
+            Helper::InRange => "in_range",
+            Helper::FromBits => "from_bits",
+            Helper::Fit => "fit",
+            Helper::Var => "var",
+            Helper::Amount => "amount",
+            Helper::Add => "add",
+            Helper::Sub => "sub",
+            Helper::Mul => "mul",
+            Helper::Neg => "neg",
+            Helper::Div => "div",
+            Helper::Rem => "rem",
+            Helper::Pow => "pow",
+            Helper::Shl => "shl",
+            Helper::Shr => "shr",
+            Helper::LogicalShr => "lshr",
+            Helper::Rotl => "rotl",
+            Helper::Rotr => "rotr",
+            Helper::Popcount => "popcount",
+            Helper::Clz => "clz",
+            Helper::Ctz => "ctz",
+            Helper::Bswap => "bswap",
+            Helper::Bitrev => "bitrev",
+            Helper::Abs => "abs",
+            Helper::Min => "min",
+            Helper::Max => "max",
+        }
+    }
+
+    fn binary(op: BinaryOp) -> Self {
+        match op {
+            BinaryOp::Add => Helper::Add,
+            BinaryOp::Sub => Helper::Sub,
+            BinaryOp::Mul => Helper::Mul,
+            BinaryOp::Div => Helper::Div,
+            BinaryOp::Rem => Helper::Rem,
+            BinaryOp::Pow => Helper::Pow,
+            BinaryOp::Shl => Helper::Shl,
+            BinaryOp::Shr => Helper::Shr,
+            BinaryOp::LogicalShr => Helper::LogicalShr,
+            BinaryOp::Rotl => Helper::Rotl,
+            BinaryOp::Rotr => Helper::Rotr,
+            BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => {
+                unreachable!("bitwise operators are emitted inline")
+            }
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "popcount" => Helper::Popcount,
+            "clz" => Helper::Clz,
+            "ctz" => Helper::Ctz,
+            "bswap" => Helper::Bswap,
+            "bitrev" => Helper::Bitrev,
+            "abs" => Helper::Abs,
+            "min" => Helper::Min,
+            "max" => Helper::Max,
+            _ => return None,
+        })
+    }
+}
+
+pub(crate) fn generate(
+    expr: &Expr,
+    language: Language,
+    options: &EvalOptions,
+) -> CalcResult<GeneratedCode> {
+    let fixed = options.precision == Precision::Fixed;
+    if !fixed && language != Language::Python {
+        return Err(CalcError::new(
+            ErrorKind::RequiresFixedWidth(language.name().to_string()),
+            expr.span(),
+        ));
+    }
+    let mut generator = Generator {
+        language,
+        options: *options,
+        bits: options.width.bits(),
+        signed: !fixed || options.signedness == Signedness::Signed,
+        fixed,
+        overflow: if fixed {
+            options.overflow
+        } else {
+            OverflowPolicy::Checked
+        },
+        helpers: BTreeSet::new(),
+    };
+    let code = generator.expr(expr)?;
+    let separator = if language == Language::Python {
+        "\n\n"
+    } else {
+        "\n"
+    };
+    let helpers = generator
+        .helpers
+        .iter()
+        .map(|helper| generator.source(*helper))
+        .collect::<Vec<_>>()
+        .join(separator);
+    Ok(GeneratedCode {
+        helpers,
+        expr: code.text(),
+    })
+}
+
+// 前に付ける部分は内側から順に積んでおき、最後に一度だけつなげる。
+// 左結合の連鎖で、それまでに組み立てた左辺を段ごとに書き写さずに済む
+struct Code {
+    prefixes: Vec<String>,
+    body: String,
+    atomic: bool,
+}
+
+impl Code {
+    fn atom(body: String) -> Self {
+        Code {
+            prefixes: Vec::new(),
+            body,
+            atomic: true,
+        }
+    }
+
+    fn infix(body: String) -> Self {
+        Code {
+            prefixes: Vec::new(),
+            body,
+            atomic: false,
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut text = String::new();
+        for prefix in self.prefixes.iter().rev() {
+            text.push_str(prefix);
+        }
+        text.push_str(&self.body);
+        text
+    }
+
+    // 演算子の被演算子やメソッドの受け手に置くときの表記
+    fn operand(&self) -> String {
+        if self.atomic {
+            self.text()
+        } else {
+            format!("({})", self.text())
+        }
+    }
+
+    // 全体の前後に before と after を付ける
+    fn wrap(mut self, before: &str, after: &str, atomic: bool) -> Self {
+        if !before.is_empty() {
+            self.prefixes.push(before.to_string());
+        }
+        self.body.push_str(after);
+        self.atomic = atomic;
+        self
+    }
+
+    // 被演算子の位置に置いてから前後を付ける
+    fn wrap_operand(self, before: &str, after: &str, atomic: bool) -> Self {
+        let code = if self.atomic {
+            self
+        } else {
+            self.wrap("(", ")", true)
+        };
+        code.wrap(before, after, atomic)
+    }
+}
+
+struct Generator {
+    language: Language,
+    options: EvalOptions,
+    bits: u32,
+    signed: bool,
+    fixed: bool,
+    overflow: OverflowPolicy,
+    helpers: BTreeSet<Helper>,
+}
+
+impl Generator {
+    fn expr(&mut self, expr: &Expr) -> CalcResult<Code> {
+        let code = match expr {
+            Expr::Paren { inner, .. } => return self.expr(inner),
+            Expr::Literal(literal) => return self.literal(expr, literal),
+            Expr::Variable { name, .. } => Ok(self.variable(name)),
+            Expr::Unary { op, operand, .. } => match (op, &**operand) {
+                (UnaryOp::Neg, Expr::Literal(literal)) => return self.literal(expr, literal),
+                (op, operand) => {
+                    let operand = self.expr(operand)?;
+                    Ok(self.unary(*op, operand))
+                }
+            },
+            Expr::Binary { .. } => {
+                // 左結合の連鎖は再帰せずに左から組み立てる
+                let (first, links) = expr.left_chain();
+                let mut acc = self.expr(first)?;
+                for (node, op, rhs) in links {
+                    let rhs = self.expr(rhs)?;
+                    acc = self
+                        .binary(op, acc, rhs)
+                        .map_err(|kind| CalcError::new(kind, node.span()))?;
+                }
+                return Ok(acc);
+            }
+            Expr::Call { name, args, .. } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<CalcResult<Vec<_>>>()?;
+                self.call(name, args)
+            }
+        };
+        code.map_err(|kind| CalcError::new(kind, expr.span()))
+    }
+
+    // リテラルは生成時に評価し、範囲外ならここでエラーにする。折り返し・飽和した値はその値を書く
+    fn literal(&mut self, expr: &Expr, literal: &Literal) -> CalcResult<Code> {
+        let value = expr.eval(&self.options)?.to_big();
+        let negative = value.is_negative();
+        let magnitude = value.abs();
+        let unchanged = magnitude == literal.value;
+        if literal.base == LiteralBase::Char && unchanged && !negative {
+            if let Some(code) = plain_char(&literal.text).and_then(|ch| self.char_literal(ch)) {
+                return Ok(code);
+            }
+        }
+
+        // 出力先の言語は 2・8・10・16 進しか書けないので、それ以外の基数は 10 進で書く
+        let radix = match literal.base {
+            LiteralBase::Binary => 2,
+            LiteralBase::Octal => 8,
+            LiteralBase::Hex | LiteralBase::Char | LiteralBase::Bytes => 16,
+            LiteralBase::Radix(radix @ (2 | 8 | 16)) => radix,
+            LiteralBase::Decimal | LiteralBase::Radix(_) => 10,
+        };
+        let text = literal.text.as_str();
+        let digits = match literal.base {
+            LiteralBase::Binary | LiteralBase::Octal | LiteralBase::Hex if unchanged => {
+                text[2..].trim_end_matches('_').to_string()
+            }
+            LiteralBase::Decimal if unchanged => text.trim_end_matches('_').to_string(),
+            _ => magnitude.magnitude_digits(radix, true),
+        };
+        Ok(self.number(negative, radix, &digits, &magnitude))
+    }
+
+    fn number(&self, negative: bool, radix: u32, digits: &str, magnitude: &BigInt) -> Code {
+        let prefix = match radix {
+            2 => "0b",
+            8 => "0o",
+            16 => "0x",
+            _ => "",
+        };
+        let text = match self.language {
+            Language::C => return self.c_number(negative, radix, digits, magnitude),
+            Language::Rust => format!("{prefix}{digits}_{}", self.rust_type()),
+            Language::Python => format!("{prefix}{digits}"),
+            Language::Verilog => {
+                let base = match radix {
+                    2 => 'b',
+                    8 => 'o',
+                    16 => 'h',
+                    _ => 'd',
+                };
+                let signed = if self.signed { "s" } else { "" };
+                format!("{}'{signed}{base}{digits}", self.bits)
+            }
+        };
+        if negative {
+            Code::infix(format!("-{text}"))
+        } else {
+            Code::atom(text)
+        }
+    }
+
+    fn c_number(&self, negative: bool, radix: u32, digits: &str, magnitude: &BigInt) -> Code {
+        let digits: String = digits.chars().filter(|ch| *ch != '_').collect();
+        let value = magnitude.to_u128().unwrap_or_default();
+        // 64 bit を超える値は C のリテラルで書けないので上位と下位に分けて組み立てる
+        let text = if value > u128::from(u64::MAX) {
+            let (high, low) = (value >> 64, value as u64);
+            let (high, low) = if radix == 2 {
+                (format!("0b{high:b}"), format!("0b{low:b}"))
+            } else {
+                (format!("0x{high:X}"), format!("0x{low:X}"))
+            };
+            format!("(((unsigned __int128){high} << 64) | {low})")
+        } else {
+            match radix {
+                2 => format!("0b{digits}"),
+                8 => format!("0{digits}"),
+                16 => format!("0x{digits}"),
+                _ if value > i64::MAX as u128 => format!("{digits}u"),
+                _ => digits,
+            }
+        };
+        match (negative, value > i64::MAX as u128) {
+            (false, false) => Code::atom(text),
+            (true, false) => Code::infix(format!("-{text}")),
+            (false, true) => Code::atom(format!("({}){text}", self.c_type())),
+            (true, true) => {
+                Code::atom(format!("({})-({}){text}", self.c_type(), self.c_unsigned()))
+            }
+        }
+    }
+
+    fn char_literal(&self, ch: char) -> Option<Code> {
+        Some(match self.language {
+            Language::C => Code::atom(format!("'{ch}'")),
+            Language::Rust if self.rust_type() == "u8" => Code::atom(format!("b'{ch}'")),
+            Language::Rust => Code::infix(format!("b'{ch}' as {}", self.rust_type())),
+            Language::Python => Code::atom(format!("ord('{ch}')")),
+            Language::Verilog => return None,
+        })
+    }
+
+    fn variable(&mut self, name: &str) -> Code {
+        match self.language {
+            Language::Python => {
+                self.require(Helper::Var);
+                Code::atom(format!("_var({name})"))
+            }
+            _ => Code::atom(name.to_string()),
+        }
+    }
+
+    fn unary(&mut self, op: UnaryOp, operand: Code) -> Code {
+        let a = operand.operand();
+        match (self.language, op) {
+            (Language::C, UnaryOp::Not) => Code::atom(format!("({})~{a}", self.c_type())),
+            (Language::Rust, UnaryOp::Not) => Code::infix(format!("!{a}")),
+            (Language::Python, UnaryOp::Not) if !self.signed => {
+                self.require(Helper::Prelude);
+                Code::infix(format!("~{a} & _MASK"))
+            }
+            (Language::Python | Language::Verilog, UnaryOp::Not) => Code::infix(format!("~{a}")),
+            (Language::Rust, UnaryOp::Neg) => match (self.overflow, self.signed) {
+                (OverflowPolicy::Saturating, false) => Code::atom(format!(
+                    "0_{}.saturating_sub({})",
+                    self.rust_type(),
+                    operand.text()
+                )),
+                _ => {
+                    let policy = self.rust_policy("neg", "");
+                    operand.wrap_operand("", &policy, true)
+                }
+            },
+            (Language::Python, UnaryOp::Neg) => {
+                self.python_fit(operand.wrap_operand("-", "", false))
+            }
+            (Language::C | Language::Verilog, UnaryOp::Neg) => {
+                self.call_helper(Helper::Neg, vec![operand])
+            }
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: Code, rhs: Code) -> Result<Code, ErrorKind> {
+        if !self.fixed && matches!(op, BinaryOp::LogicalShr | BinaryOp::Rotl | BinaryOp::Rotr) {
+            return Err(ErrorKind::RequiresFixedWidth(op.symbol().to_string()));
+        }
+        if matches!(op, BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor) {
+            let code = lhs.wrap_operand("", &format!(" {} {}", op.symbol(), rhs.operand()), false);
+            return Ok(match self.language {
+                Language::C => code.wrap(&format!("({})(", self.c_type()), ")", true),
+                _ => code,
+            });
+        }
+        Ok(match self.language {
+            Language::C | Language::Verilog => self.call_helper(Helper::binary(op), vec![lhs, rhs]),
+            Language::Rust => self.rust_binary(op, lhs, rhs),
+            Language::Python => self.python_binary(op, lhs, rhs),
+        })
+    }
+
+    fn rust_binary(&mut self, op: BinaryOp, lhs: Code, rhs: Code) -> Code {
+        let b = rhs.text();
+        match op {
+            BinaryOp::Add => lhs.wrap_operand("", &self.rust_policy("add", &b), true),
+            BinaryOp::Sub => lhs.wrap_operand("", &self.rust_policy("sub", &b), true),
+            BinaryOp::Mul => lhs.wrap_operand("", &self.rust_policy("mul", &b), true),
+            // 符号なしでは切り捨てと Euclidean が一致する
+            BinaryOp::Div | BinaryOp::Rem if !self.signed => {
+                lhs.wrap_operand("", &format!(" {} {}", op.symbol(), rhs.operand()), false)
+            }
+            BinaryOp::Div => match self.overflow {
+                OverflowPolicy::Checked => {
+                    lhs.wrap_operand("", &format!(".checked_div_euclid({b}).unwrap()"), true)
+                }
+                OverflowPolicy::Wrapping => {
+                    lhs.wrap_operand("", &format!(".wrapping_div_euclid({b})"), true)
+                }
+                OverflowPolicy::Saturating => self.call_helper(Helper::Div, vec![lhs, rhs]),
+            },
+            // MIN % -1 は 128-bit のときだけエラーになる
+            BinaryOp::Rem if self.bits == 128 => {
+                lhs.wrap_operand("", &format!(".checked_rem_euclid({b}).unwrap()"), true)
+            }
+            BinaryOp::Rem => lhs.wrap_operand("", &format!(".wrapping_rem_euclid({b})"), true),
+            BinaryOp::Pow | BinaryOp::Shl => self.call_helper(Helper::binary(op), vec![lhs, rhs]),
+            BinaryOp::LogicalShr if self.signed => {
+                self.require(Helper::Amount);
+                let after = format!(
+                    " as {}) >> calc_amount({b})) as {}",
+                    self.rust_unsigned(),
+                    self.rust_type()
+                );
+                lhs.wrap_operand("((", &after, false)
+            }
+            BinaryOp::Shr | BinaryOp::LogicalShr => {
+                self.require(Helper::Amount);
+                lhs.wrap_operand("", &format!(" >> calc_amount({b})"), false)
+            }
+            BinaryOp::Rotl | BinaryOp::Rotr => {
+                self.require(Helper::Amount);
+                let method = if op == BinaryOp::Rotl {
+                    "rotate_left"
+                } else {
+                    "rotate_right"
+                };
+                lhs.wrap_operand("", &format!(".{method}(calc_amount({b}))"), true)
+            }
+            BinaryOp::BitAnd | BinaryOp::BitOr | BinaryOp::BitXor => unreachable!(),
+        }
+    }
+
+    fn python_binary(&mut self, op: BinaryOp, lhs: Code, rhs: Code) -> Code {
+        match op {
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul => {
+                let after = format!(" {} {}", op.symbol(), rhs.operand());
+                self.python_fit(lhs.wrap_operand("", &after, false))
+            }
+            BinaryOp::Shr => {
+                self.require(Helper::Amount);
+                lhs.wrap_operand("", &format!(" >> _amount({})", rhs.text()), false)
+            }
+            BinaryOp::LogicalShr => {
+                self.require(Helper::Amount);
+                self.require(Helper::FromBits);
+                let after = format!(" & _MASK) >> _amount({}))", rhs.text());
+                lhs.wrap_operand("_from_bits((", &after, true)
+            }
+            _ => self.call_helper(Helper::binary(op), vec![lhs, rhs]),
+        }
+    }
+
+    fn call(&mut self, name: &str, mut args: Vec<Code>) -> Result<Code, ErrorKind> {
+        let builtin =
+            builtins::lookup(name).ok_or_else(|| ErrorKind::UnknownFunction(name.to_string()))?;
+        if args.len() != builtin.arity {
+            return Err(ErrorKind::ArityMismatch {
+                name: name.to_string(),
+                expected: builtin.arity,
+                found: args.len(),
+            });
+        }
+        let helper =
+            Helper::builtin(name).ok_or_else(|| ErrorKind::UnknownFunction(name.to_string()))?;
+        if !self.fixed && !matches!(helper, Helper::Abs | Helper::Min | Helper::Max) {
+            return Err(ErrorKind::RequiresFixedWidth(name.to_string()));
+        }
+        let a = args[0].operand();
+        Ok(match (self.language, helper) {
+            (Language::C, _) => self.call_helper(helper, args),
+            (_, Helper::Abs) if !self.signed => args.swap_remove(0),
+            (Language::Verilog, Helper::Bswap) if self.bits == 8 => args.swap_remove(0),
+            (Language::Verilog, _) => self.call_helper(helper, args),
+            (Language::Rust, Helper::Popcount | Helper::Clz | Helper::Ctz) => {
+                let method = match helper {
+                    Helper::Popcount => "count_ones",
+                    Helper::Clz => "leading_zeros",
+                    _ => "trailing_zeros",
+                };
+                Code::infix(format!("{a}.{method}() as {}", self.rust_type()))
+            }
+            (Language::Rust, Helper::Bswap) => Code::atom(format!("{a}.swap_bytes()")),
+            (Language::Rust, Helper::Bitrev) => Code::atom(format!("{a}.reverse_bits()")),
+            (Language::Rust, Helper::Abs) => Code::atom(format!("{a}.checked_abs().unwrap()")),
+            (Language::Rust, _) => Code::atom(format!("{a}.{}({})", helper.name(), args[1].text())),
+            (Language::Python, Helper::Popcount) => {
+                self.require(Helper::Prelude);
+                Code::atom(format!("bin({a} & _MASK).count(\"1\")"))
+            }
+            (Language::Python, Helper::Clz) => {
+                self.require(Helper::Prelude);
+                Code::infix(format!("_BITS - ({a} & _MASK).bit_length()"))
+            }
+            (Language::Python, Helper::Abs) if !self.fixed => {
+                Code::atom(format!("abs({})", args[0].text()))
+            }
+            (Language::Python, Helper::Min | Helper::Max) => Code::atom(format!(
+                "{}({}, {})",
+                helper.name(),
+                args[0].text(),
+                args[1].text()
+            )),
+            (Language::Python, _) => self.call_helper(helper, args),
+        })
+    }
+
+    // 第 1 引数には連鎖の左辺が来るので、書き写さずに前後を付ける
+    fn call_helper(&mut self, helper: Helper, args: Vec<Code>) -> Code {
+        self.require(helper);
+        let prefix = if self.language == Language::Python {
+            "_"
+        } else {
+            "calc_"
+        };
+        let mut args = args.into_iter();
+        let first = args.next().expect("helpers take at least one argument");
+        let rest: String = args.map(|arg| format!(", {}", arg.text())).collect();
+        first.wrap(
+            &format!("{prefix}{}(", helper.name()),
+            &format!("{rest})"),
+            true,
+        )
+    }
+
+    fn python_fit(&mut self, code: Code) -> Code {
+        self.require(Helper::Fit);
+        code.wrap("_fit(", ")", true)
+    }
+
+    // 受け手の後ろに付けるメソッド呼び出し
+    fn rust_policy(&self, method: &str, arg: &str) -> String {
+        match self.overflow {
+            OverflowPolicy::Checked => format!(".checked_{method}({arg}).unwrap()"),
+            OverflowPolicy::Wrapping => format!(".wrapping_{method}({arg})"),
+            OverflowPolicy::Saturating => format!(".saturating_{method}({arg})"),
+        }
+    }
+
+    fn require(&mut self, helper: Helper) {
+        if self.helpers.insert(helper) {
+            for dependency in self.dependencies(helper) {
+                self.require(dependency);
+            }
+        }
+    }
+
+    fn dependencies(&self, helper: Helper) -> Vec<Helper> {
+        match self.language {
+            Language::C => vec![Helper::Prelude],
+            Language::Rust if helper == Helper::Shl => vec![Helper::Amount],
+            Language::Rust | Language::Verilog => Vec::new(),
+            Language::Python => match helper {
+                Helper::Prelude => Vec::new(),
+                Helper::Fit => match self.overflow {
+                    OverflowPolicy::Checked => vec![Helper::InRange],
+                    OverflowPolicy::Wrapping => vec![Helper::FromBits],
+                    OverflowPolicy::Saturating => vec![Helper::Prelude],
+                },
+                Helper::Pow if self.overflow == OverflowPolicy::Wrapping => {
+                    vec![Helper::FromBits]
+                }
+                Helper::Div | Helper::Pow => vec![Helper::Fit],
+                Helper::Var | Helper::Abs => vec![Helper::InRange],
+                Helper::Shl => vec![Helper::Amount, Helper::InRange],
+                Helper::Rotl | Helper::Rotr => vec![Helper::Amount, Helper::FromBits],
+                Helper::Bswap | Helper::Bitrev => vec![Helper::FromBits],
+                _ => vec![Helper::Prelude],
+            },
+        }
+    }
+
+    fn source(&self, helper: Helper) -> String {
+        match self.language {
+            Language::C => self.c_source(helper),
+            Language::Rust => self.rust_source(helper),
+            Language::Python => self.python_source(helper),
+            Language::Verilog => self.verilog_source(helper),
+        }
+    }
+
+    // テンプレート中の $T などを型や定数に置き換える
+    fn fill(&self, template: &str) -> String {
+        let n = self.bits;
+        let (c_min, c_max) = self.c_limits();
+        let (v_min, v_max) = if self.signed {
+            (
+                format!("{{1'b1, {{{}{{1'b0}}}}}}", n - 1),
+                format!("{{1'b0, {{{}{{1'b1}}}}}}", n - 1),
+            )
+        } else {
+            (format!("{{{n}{{1'b0}}}}"), format!("{{{n}{{1'b1}}}}"))
+        };
+        let (min, max) = match self.language {
+            Language::Verilog => (v_min, v_max),
+            _ => (c_min, c_max),
+        };
+        let ty = match self.language {
+            Language::Rust => self.rust_type(),
+            _ => self.c_type(),
+        };
+        let unsigned = match self.language {
+            Language::Rust => self.rust_unsigned(),
+            _ => self.c_unsigned(),
+        };
+        let signed = if self.signed { "signed " } else { "" };
+        // 前方一致する名前があるので長いものから置き換える
+        let replacements = [
+            ("$DHI", (2 * n - 1).to_string()),
+            ("$MIN", min),
+            ("$MAX", max),
+            ("$HI", (n - 1).to_string()),
+            ("$WP", (n + 1).to_string()),
+            ("$T", ty),
+            ("$U", unsigned),
+            ("$V", format!("{signed}[{}:0]", n - 1)),
+            ("$S", signed.to_string()),
+            ("$W", n.to_string()),
+            ("$X", format!("{{{n}{{1'bx}}}}")),
+        ];
+        replacements
+            .iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(name, value)
+            })
+    }
+
+    fn c_type(&self) -> String {
+        match (self.bits, self.signed) {
+            (128, true) => "__int128".to_string(),
+            (128, false) => "unsigned __int128".to_string(),
+            (bits, true) => format!("int{bits}_t"),
+            (bits, false) => format!("uint{bits}_t"),
+        }
+    }
+
+    fn c_unsigned(&self) -> String {
+        match self.bits {
+            128 => "unsigned __int128".to_string(),
+            bits => format!("uint{bits}_t"),
+        }
+    }
+
+    fn c_limits(&self) -> (String, String) {
+        match (self.bits, self.signed) {
+            (128, true) => ("CALC_INT128_MIN".to_string(), "CALC_INT128_MAX".to_string()),
+            (128, false) => ("0".to_string(), "CALC_UINT128_MAX".to_string()),
+            (bits, true) => (format!("INT{bits}_MIN"), format!("INT{bits}_MAX")),
+            (bits, false) => ("0".to_string(), format!("UINT{bits}_MAX")),
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        format!("{}{}", if self.signed { 'i' } else { 'u' }, self.bits)
+    }
+
+    fn rust_unsigned(&self) -> String {
+        format!("u{}", self.bits)
+    }
+
+    fn c_function(&self, helper: Helper, lines: &[String]) -> String {
+        let params = match helper {
+            Helper::Neg
+            | Helper::Popcount
+            | Helper::Clz
+            | Helper::Ctz
+            | Helper::Bswap
+            | Helper::Bitrev
+            | Helper::Abs => "$T a",
+            _ => "$T a, $T b",
+        };
+        let mut out = self.fill(&format!(
+            "static inline $T calc_{}({params})\n{{\n",
+            helper.name()
+        ));
+        for line in lines {
+            out.push_str(&self.fill(&format!("    {line}\n")));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    // オーバーフロー検査付きの組み込み関数を呼ぶ行
+    fn c_checked(&self, call: &str, saturated: &str) -> Vec<String> {
+        match self.overflow {
+            OverflowPolicy::Checked => lines(&[&format!("if ({call})"), "    abort();"]),
+            OverflowPolicy::Wrapping => lines(&[&format!("{call};")]),
+            OverflowPolicy::Saturating => {
+                lines(&[&format!("if ({call})"), &format!("    return {saturated};")])
+            }
+        }
+    }
+
+    fn c_amount_check(&self) -> Vec<String> {
+        let check = if self.signed {
+            "if (b < 0 || b >= $W)"
+        } else {
+            "if (b >= $W)"
+        };
+        lines(&[check, "    abort();"])
+    }
+
+    fn c_source(&self, helper: Helper) -> String {
+        let signed = self.signed;
+        let wide = self.bits == 128;
+        let body = match helper {
+            Helper::Prelude => {
+                let mut out = String::from("#include <stdint.h>\n#include <stdlib.h>\n");
+                if wide && signed {
+                    out.push_str(
+                        "\n#define CALC_INT128_MAX ((__int128)(~(unsigned __int128)0 >> 1))\n\
+                         #define CALC_INT128_MIN (-CALC_INT128_MAX - 1)\n",
+                    );
+                } else if wide {
+                    out.push_str("\n#define CALC_UINT128_MAX (~(unsigned __int128)0)\n");
+                }
+                return out;
+            }
+            Helper::Add | Helper::Sub | Helper::Mul => {
+                let saturated = match (helper, signed) {
+                    (Helper::Add, true) => "b < 0 ? $MIN : $MAX",
+                    (Helper::Sub, true) => "b < 0 ? $MAX : $MIN",
+                    (Helper::Mul, true) => "(a < 0) != (b < 0) ? $MIN : $MAX",
+                    (Helper::Sub, false) => "0",
+                    _ => "$MAX",
+                };
+                let call = format!("__builtin_{}_overflow(a, b, &r)", helper.name());
+                let mut body = lines(&["$T r;"]);
+                body.extend(self.c_checked(&call, saturated));
+                body.push("return r;".to_string());
+                body
+            }
+            Helper::Neg => {
+                let saturated = if signed { "$MAX" } else { "0" };
+                let mut body = lines(&["$T r;"]);
+                body.extend(self.c_checked("__builtin_sub_overflow(($T)0, a, &r)", saturated));
+                body.push("return r;".to_string());
+                body
+            }
+            Helper::Div if signed => {
+                let overflow = match self.overflow {
+                    OverflowPolicy::Checked => "abort();",
+                    OverflowPolicy::Wrapping => "return a;",
+                    OverflowPolicy::Saturating => "return $MAX;",
+                };
+                lines(&[
+                    "$T q;",
+                    "if (b == 0)",
+                    "    abort();",
+                    "if (a == $MIN && b == -1)",
+                    &format!("    {overflow}"),
+                    "q = a / b;",
+                    "if (a % b < 0)",
+                    "    q = b > 0 ? q - 1 : q + 1;",
+                    "return q;",
+                ])
+            }
+            Helper::Rem if signed => {
+                let mut body = lines(&["$T r;", "if (b == 0)", "    abort();"]);
+                if wide {
+                    body.extend(lines(&["if (a == $MIN && b == -1)", "    abort();"]));
+                }
+                body.extend(lines(&[
+                    "if (b == -1)",
+                    "    return 0;",
+                    "r = a % b;",
+                    "if (r < 0)",
+                    "    r = b < 0 ? r - b : r + b;",
+                    "return r;",
+                ]));
+                body
+            }
+            Helper::Div | Helper::Rem => {
+                let op = if helper == Helper::Div { '/' } else { '%' };
+                lines(&["if (b == 0)", "    abort();", &format!("return a {op} b;")])
+            }
+            Helper::Pow => {
+                let tracked = self.overflow != OverflowPolicy::Wrapping;
+                let mut body = lines(&["$T r = 1;"]);
+                if tracked {
+                    body.push("int overflow = 0;".to_string());
+                }
+                if signed {
+                    body.extend(lines(&["if (b < 0)", "    abort();"]));
+                }
+                // 底が 0, 1, -1 なら指数が大きくても範囲内に収まる
+                if self.bits > 32 {
+                    let guard = if signed {
+                        "if (b > 0xFFFFFFFF && (a < -1 || a > 1))"
+                    } else {
+                        "if (b > 0xFFFFFFFF && a > 1)"
+                    };
+                    body.extend(lines(&[guard, "    abort();"]));
+                }
+                if signed && self.overflow == OverflowPolicy::Saturating {
+                    body.push("int negative = a < 0 && (b & 1);".to_string());
+                }
+                let flag = if tracked { "overflow |= " } else { "" };
+                body.extend(lines(&[
+                    "for (;;) {",
+                    "    if (b & 1)",
+                    &format!("        {flag}__builtin_mul_overflow(r, a, &r);"),
+                    "    b >>= 1;",
+                    "    if (b == 0)",
+                    "        break;",
+                    &format!("    {flag}__builtin_mul_overflow(a, a, &a);"),
+                    "}",
+                ]));
+                match self.overflow {
+                    OverflowPolicy::Checked => {
+                        body.extend(lines(&["if (overflow)", "    abort();"]))
+                    }
+                    OverflowPolicy::Wrapping => {}
+                    OverflowPolicy::Saturating => body.extend(lines(&[
+                        "if (overflow)",
+                        if signed {
+                            "    return negative ? $MIN : $MAX;"
+                        } else {
+                            "    return $MAX;"
+                        },
+                    ])),
+                }
+                body.push("return r;".to_string());
+                body
+            }
+            Helper::Shl => {
+                let mut body = lines(&["$T r;"]);
+                body.extend(self.c_amount_check());
+                body.extend(lines(&[
+                    "r = ($T)(($U)a << b);",
+                    "if (($T)(r >> b) != a)",
+                    "    abort();",
+                    "return r;",
+                ]));
+                body
+            }
+            Helper::Shr | Helper::LogicalShr | Helper::Rotl | Helper::Rotr => {
+                let mut body = self.c_amount_check();
+                body.extend(match helper {
+                    Helper::Shr => lines(&["return ($T)(a >> b);"]),
+                    Helper::LogicalShr => lines(&["return ($T)(($U)a >> b);"]),
+                    Helper::Rotl => lines(&[
+                        "if (b == 0)",
+                        "    return a;",
+                        "return ($T)((($U)a << b) | (($U)a >> ($W - b)));",
+                    ]),
+                    _ => lines(&[
+                        "if (b == 0)",
+                        "    return a;",
+                        "return ($T)((($U)a >> b) | (($U)a << ($W - b)));",
+                    ]),
+                });
+                body
+            }
+            Helper::Popcount if wide => lines(&[
+                "$U x = ($U)a;",
+                "return ($T)(__builtin_popcountll((uint64_t)x) + __builtin_popcountll((uint64_t)(x >> 64)));",
+            ]),
+            Helper::Popcount => lines(&["return ($T)__builtin_popcountll(($U)a);"]),
+            Helper::Clz if wide => lines(&[
+                "$U x = ($U)a;",
+                "uint64_t high = (uint64_t)(x >> 64), low = (uint64_t)x;",
+                "if (high != 0)",
+                "    return ($T)__builtin_clzll(high);",
+                "if (low != 0)",
+                "    return ($T)(64 + __builtin_clzll(low));",
+                "return 128;",
+            ]),
+            Helper::Clz => lines(&[
+                "if (a == 0)",
+                "    return $W;",
+                "return ($T)(__builtin_clzll(($U)a) - (64 - $W));",
+            ]),
+            Helper::Ctz if wide => lines(&[
+                "$U x = ($U)a;",
+                "uint64_t high = (uint64_t)(x >> 64), low = (uint64_t)x;",
+                "if (low != 0)",
+                "    return ($T)__builtin_ctzll(low);",
+                "if (high != 0)",
+                "    return ($T)(64 + __builtin_ctzll(high));",
+                "return 128;",
+            ]),
+            Helper::Ctz => lines(&[
+                "if (a == 0)",
+                "    return $W;",
+                "return ($T)__builtin_ctzll(($U)a);",
+            ]),
+            Helper::Bswap => match self.bits {
+                8 => lines(&["return a;"]),
+                128 => lines(&[
+                    "$U x = ($U)a;",
+                    "return ($T)((($U)__builtin_bswap64((uint64_t)x) << 64) | __builtin_bswap64((uint64_t)(x >> 64)));",
+                ]),
+                _ => lines(&["return ($T)__builtin_bswap$W(($U)a);"]),
+            },
+            Helper::Bitrev => lines(&[
+                "$U x = ($U)a, r = 0;",
+                "int i;",
+                "for (i = 0; i < $W; i++) {",
+                "    r = ($U)(r << 1) | (x & 1);",
+                "    x >>= 1;",
+                "}",
+                "return ($T)r;",
+            ]),
+            Helper::Abs if signed => lines(&[
+                "if (a == $MIN)",
+                "    abort();",
+                "return a < 0 ? -a : a;",
+            ]),
+            Helper::Abs => lines(&["return a;"]),
+            Helper::Min => lines(&["return a < b ? a : b;"]),
+            Helper::Max => lines(&["return a > b ? a : b;"]),
+            Helper::InRange | Helper::FromBits | Helper::Fit | Helper::Var | Helper::Amount => {
+                unreachable!("not used by the C backend")
+            }
+        };
+        self.c_function(helper, &body)
+    }
+
+    fn rust_source(&self, helper: Helper) -> String {
+        let source = match helper {
+            Helper::Amount => {
+                "fn calc_amount(b: $T) -> u32 {\n    \
+                 u32::try_from(b)\n        \
+                 .ok()\n        \
+                 .filter(|&n| n < $T::BITS)\n        \
+                 .expect(\"shift amount out of range\")\n\
+                 }\n"
+            }
+            Helper::Shl => {
+                "fn calc_shl(a: $T, b: $T) -> $T {\n    \
+                 let n = calc_amount(b);\n    \
+                 let r = a << n;\n    \
+                 assert!(r >> n == a, \"shift overflow\");\n    \
+                 r\n\
+                 }\n"
+            }
+            Helper::Div => {
+                "fn calc_div(a: $T, b: $T) -> $T {\n    \
+                 if b == -1 {\n        \
+                 a.saturating_neg()\n    \
+                 } else {\n        \
+                 a.div_euclid(b)\n    \
+                 }\n\
+                 }\n"
+            }
+            // 底が 0, 1, -1 なら結果は指数の偶奇だけで決まる
+            Helper::Pow => {
+                let pow = self.rust_policy("pow", "e");
+                return self.fill(&format!(
+                    "fn calc_pow(a: $T, b: $T) -> $T {{\n    \
+                     let e = match u32::try_from(b) {{\n        \
+                     Ok(e) => e,\n        \
+                     Err(_) if b > 0 && a.abs_diff(0) <= 1 => 2 + (b % 2) as u32,\n        \
+                     Err(_) => panic!(\"exponent out of range\"),\n    \
+                     }};\n    \
+                     a{pow}\n\
+                     }}\n"
+                ));
+            }
+            _ => unreachable!("not used by the Rust backend"),
+        };
+        self.fill(source)
+    }
+
+    fn python_function(&self, helper: Helper, lines: &[&str]) -> String {
+        let params = match helper {
+            Helper::InRange | Helper::FromBits | Helper::Fit | Helper::Var => "value",
+            Helper::Amount => "b",
+            Helper::Rem | Helper::Div | Helper::Pow | Helper::Shl | Helper::Rotl | Helper::Rotr => {
+                "a, b"
+            }
+            _ => "a",
+        };
+        let mut out = format!("def _{}({params}):\n", helper.name());
+        for line in lines {
+            out.push_str(&format!("    {line}\n"));
+        }
+        out
+    }
+
+    fn python_source(&self, helper: Helper) -> String {
+        let limit = if self.fixed { "_BITS" } else { "_MAX_BITS" };
+        match helper {
+            Helper::Prelude if self.fixed => {
+                let (min, max) = if self.signed {
+                    ("-(1 << (_BITS - 1))", "(1 << (_BITS - 1)) - 1")
+                } else {
+                    ("0", "_MASK")
+                };
+                format!(
+                    "_BITS = {}\n_MASK = (1 << _BITS) - 1\n_MIN = {min}\n_MAX = {max}\n",
+                    self.bits
+                )
+            }
+            Helper::Prelude => format!("_MAX_BITS = {}\n", crate::int::BIG_MAX_BITS),
+            Helper::InRange if self.fixed => {
+                self.python_function(helper, &["return _MIN <= value <= _MAX"])
+            }
+            Helper::InRange => {
+                self.python_function(helper, &["return value.bit_length() <= _MAX_BITS"])
+            }
+            Helper::FromBits => self.python_function(
+                helper,
+                &["return value - (1 << _BITS) if value > _MAX else value"],
+            ),
+            Helper::Fit => match self.overflow {
+                OverflowPolicy::Checked => self.python_function(
+                    helper,
+                    &[
+                        "if not _in_range(value):",
+                        "    raise OverflowError(f\"{value} is out of range\")",
+                        "return value",
+                    ],
+                ),
+                OverflowPolicy::Wrapping => {
+                    self.python_function(helper, &["return _from_bits(value & _MASK)"])
+                }
+                OverflowPolicy::Saturating => {
+                    self.python_function(helper, &["return min(max(value, _MIN), _MAX)"])
+                }
+            },
+            Helper::Var => self.python_function(
+                helper,
+                &[
+                    "if not _in_range(value):",
+                    "    raise OverflowError(f\"{value} does not fit the current width\")",
+                    "return value",
+                ],
+            ),
+            Helper::Amount => self.python_function(
+                helper,
+                &[
+                    &format!("if not 0 <= b < {limit}:"),
+                    "    raise ValueError(\"shift amount out of range\")",
+                    "return b",
+                ],
+            ),
+            Helper::Div => self.python_function(
+                helper,
+                &[
+                    "if b == 0:",
+                    "    raise ZeroDivisionError(\"division by zero\")",
+                    "quotient, remainder = divmod(a, b)",
+                    "return _fit(quotient + 1 if remainder < 0 else quotient)",
+                ],
+            ),
+            Helper::Rem => {
+                let mut body = vec!["if b == 0:", "    raise ZeroDivisionError(\"remainder by zero\")"];
+                if self.fixed && self.signed && self.bits == 128 {
+                    body.extend([
+                        "if a == _MIN and b == -1:",
+                        "    raise OverflowError(\"remainder overflow\")",
+                    ]);
+                }
+                body.push("return a % abs(b)");
+                self.python_function(helper, &body)
+            }
+            Helper::Pow => {
+                let mut body = vec![
+                    "if b < 0:",
+                    "    raise ValueError(\"negative exponent\")",
+                    "if b > 0xFFFFFFFF and abs(a) > 1:",
+                    "    raise OverflowError(\"exponent out of range\")",
+                ];
+                // |a| >= 2 で指数が上限を超えると必ず範囲外なので、巨大な値を作らずに済ませる
+                let guard = format!("if abs(a) > 1 and b > {limit}:");
+                let sentinel = format!(
+                    "    return _fit(-(1 << ({limit} + 1)) if a < 0 and b % 2 else 1 << ({limit} + 1))"
+                );
+                if self.overflow == OverflowPolicy::Wrapping {
+                    body.push("return _from_bits(pow(a, b, 1 << _BITS))");
+                } else {
+                    body.extend([guard.as_str(), sentinel.as_str(), "return _fit(a ** b)"]);
+                }
+                self.python_function(helper, &body)
+            }
+            Helper::Shl => self.python_function(
+                helper,
+                &[
+                    "value = a << _amount(b)",
+                    "if not _in_range(value):",
+                    "    raise OverflowError(\"shift overflow\")",
+                    "return value",
+                ],
+            ),
+            Helper::Rotl => self.python_function(
+                helper,
+                &[
+                    "n = _amount(b)",
+                    "bits = a & _MASK",
+                    "return _from_bits(((bits << n) | (bits >> (_BITS - n))) & _MASK)",
+                ],
+            ),
+            Helper::Rotr => self.python_function(
+                helper,
+                &[
+                    "n = _amount(b)",
+                    "bits = a & _MASK",
+                    "return _from_bits(((bits >> n) | (bits << (_BITS - n))) & _MASK)",
+                ],
+            ),
+            Helper::Ctz => self.python_function(
+                helper,
+                &[
+                    "bits = a & _MASK",
+                    "return (bits & -bits).bit_length() - 1 if bits else _BITS",
+                ],
+            ),
+            Helper::Bswap => self.python_function(
+                helper,
+                &["return _from_bits(int.from_bytes((a & _MASK).to_bytes(_BITS // 8, \"big\"), \"little\"))"],
+            ),
+            Helper::Bitrev => self.python_function(
+                helper,
+                &["return _from_bits(int(format(a & _MASK, f\"0{_BITS}b\")[::-1], 2))"],
+            ),
+            Helper::Abs => self.python_function(
+                helper,
+                &[
+                    "if not _in_range(abs(a)):",
+                    "    raise OverflowError(f\"abs({a}) is out of range\")",
+                    "return abs(a)",
+                ],
+            ),
+            _ => unreachable!("not used by the Python backend"),
+        }
+    }
+
+    fn verilog_function(&self, helper: Helper, decls: &[&str], lines: &[String]) -> String {
+        let name = helper.name();
+        let params = match helper {
+            Helper::Neg
+            | Helper::Popcount
+            | Helper::Clz
+            | Helper::Ctz
+            | Helper::Bswap
+            | Helper::Bitrev
+            | Helper::Abs => "input $V a",
+            _ => "input $V a, input $V b",
+        };
+        let mut out = format!("function automatic $V calc_{name}({params});\n");
+        for decl in decls {
+            out.push_str(&format!("    {decl}\n"));
+        }
+        out.push_str("    begin\n");
+        for line in lines {
+            out.push_str(&format!(
+                "        {}\n",
+                line.replace("$F", &format!("calc_{name}"))
+            ));
+        }
+        out.push_str("    end\nendfunction\n");
+        self.fill(&out)
+    }
+
+    // 検査・折り返し・飽和のどれで結果を返すか
+    fn verilog_select(&self, overflow: &str, saturated: &str, wrapped: &str) -> String {
+        match self.overflow {
+            OverflowPolicy::Checked => format!("$F = ({overflow}) ? $X : {wrapped};"),
+            OverflowPolicy::Wrapping => format!("$F = {wrapped};"),
+            OverflowPolicy::Saturating => format!("$F = ({overflow}) ? {saturated} : {wrapped};"),
+        }
+    }
+
+    fn verilog_amount_check(&self) -> &'static str {
+        if self.signed {
+            "b < 0 || b >= $W"
+        } else {
+            "b >= $W"
+        }
+    }
+
+    fn verilog_source(&self, helper: Helper) -> String {
+        let signed = self.signed;
+        let x = |cond: &str, value: &str| format!("$F = ({cond}) ? $X : {value};");
+        // 2N bit の積が N bit に収まらない
+        let wide_overflow = |name: &str| {
+            if signed {
+                format!(
+                    "{name}[$DHI:$HI] != {{$WP{{1'b0}}}} && {name}[$DHI:$HI] != {{$WP{{1'b1}}}}"
+                )
+            } else {
+                format!("{name}[$DHI:$W] != 0")
+            }
+        };
+        match helper {
+            Helper::Add | Helper::Sub => {
+                let op = if helper == Helper::Add { '+' } else { '-' };
+                let (overflow, saturated) = match (helper, signed) {
+                    (_, true) => ("r[$W] != r[$HI]", "(r[$W] ? $MIN : $MAX)"),
+                    (Helper::Add, false) => ("r[$W]", "$MAX"),
+                    _ => ("r[$W]", "$MIN"),
+                };
+                self.verilog_function(
+                    helper,
+                    &["reg $S[$W:0] r;"],
+                    &[
+                        format!("r = a {op} b;"),
+                        self.verilog_select(overflow, saturated, "r[$HI:0]"),
+                    ],
+                )
+            }
+            Helper::Mul => {
+                let saturated = if signed {
+                    "(r[$DHI] ? $MIN : $MAX)"
+                } else {
+                    "$MAX"
+                };
+                self.verilog_function(
+                    helper,
+                    &["reg $S[$DHI:0] r;"],
+                    &[
+                        "r = a * b;".to_string(),
+                        self.verilog_select(&wide_overflow("r"), saturated, "r[$HI:0]"),
+                    ],
+                )
+            }
+            Helper::Neg => {
+                let (overflow, saturated) = if signed {
+                    ("r[$W] != r[$HI]", "$MAX")
+                } else {
+                    ("a != 0", "$MIN")
+                };
+                self.verilog_function(
+                    helper,
+                    &["reg $S[$W:0] r;"],
+                    &[
+                        "r = -a;".to_string(),
+                        self.verilog_select(overflow, saturated, "r[$HI:0]"),
+                    ],
+                )
+            }
+            Helper::Div if signed => self.verilog_function(
+                helper,
+                &["reg signed [$W:0] q;", "reg signed [$W:0] r;"],
+                &[
+                    "if (b == 0)".to_string(),
+                    "    $F = $X;".to_string(),
+                    "else begin".to_string(),
+                    "    q = a / b;".to_string(),
+                    "    r = a % b;".to_string(),
+                    "    if (r < 0)".to_string(),
+                    "        q = (b > 0) ? q - 1 : q + 1;".to_string(),
+                    format!(
+                        "    {}",
+                        self.verilog_select("q[$W] != q[$HI]", "$MAX", "q[$HI:0]")
+                    ),
+                    "end".to_string(),
+                ],
+            ),
+            Helper::Rem if signed => {
+                let mut body = lines(&["if (b == 0)", "    $F = $X;"]);
+                if self.bits == 128 {
+                    body.extend(lines(&["else if (a == $MIN && b == -1)", "    $F = $X;"]));
+                }
+                body.extend(lines(&[
+                    "else begin",
+                    "    r = a % b;",
+                    "    if (r < 0)",
+                    "        r = (b < 0) ? r - b : r + b;",
+                    "    $F = r[$HI:0];",
+                    "end",
+                ]));
+                self.verilog_function(helper, &["reg signed [$W:0] r;"], &body)
+            }
+            Helper::Div | Helper::Rem => {
+                let op = if helper == Helper::Div { '/' } else { '%' };
+                self.verilog_function(helper, &[], &[x("b == 0", &format!("a {op} b"))])
+            }
+            Helper::Pow => {
+                let mut guards = Vec::new();
+                if signed {
+                    guards.push("b < 0");
+                }
+                // 底が 0, 1, -1 なら指数は偶奇だけを残して 32 bit に収める
+                let exponent = if self.bits > 32 {
+                    guards.push(if signed {
+                        "b > 32'hFFFFFFFF && (a < -1 || a > 1)"
+                    } else {
+                        "b > 32'hFFFFFFFF && a > 1"
+                    });
+                    "e = (b > 32'hFFFFFFFF) ? {31'b1, b[0]} : b[31:0];"
+                } else {
+                    "e = b;"
+                };
+                let saturated = if signed {
+                    "((a < 0 && b[0]) ? $MIN : $MAX)"
+                } else {
+                    "$MAX"
+                };
+                let overflow = wide_overflow("wide");
+                let mut body = lines(&[
+                    "r = 1;",
+                    "base = a;",
+                    exponent,
+                    "overflow = 0;",
+                    "for (i = 0; i < 32; i = i + 1) begin",
+                    "    if (e[0]) begin",
+                    "        wide = r * base;",
+                    &format!("        overflow = overflow | ({overflow});"),
+                    "        r = wide[$HI:0];",
+                    "    end",
+                    "    e = e >> 1;",
+                    "    if (e != 0) begin",
+                    "        wide = base * base;",
+                    &format!("        overflow = overflow | ({overflow});"),
+                    "        base = wide[$HI:0];",
+                    "    end",
+                    "end",
+                ]);
+                body.push(self.verilog_select("overflow", saturated, "r"));
+                if !guards.is_empty() {
+                    let mut guarded = lines(&[
+                        &format!("if ({})", guards.join(" || ")),
+                        "    $F = $X;",
+                        "else begin",
+                    ]);
+                    guarded.extend(body.iter().map(|line| format!("    {line}")));
+                    guarded.push("end".to_string());
+                    body = guarded;
+                }
+                self.verilog_function(
+                    helper,
+                    &[
+                        "reg $S[$DHI:0] wide;",
+                        "reg $V base;",
+                        "reg $V r;",
+                        "reg [31:0] e;",
+                        "reg overflow;",
+                        "integer i;",
+                    ],
+                    &body,
+                )
+            }
+            Helper::Shl => self.verilog_function(
+                helper,
+                &["reg $V r;"],
+                &lines(&[
+                    &format!("if ({})", self.verilog_amount_check()),
+                    "    $F = $X;",
+                    "else begin",
+                    "    r = a << b;",
+                    "    $F = ((r >>> b) != a) ? $X : r;",
+                    "end",
+                ]),
+            ),
+            Helper::Shr => {
+                self.verilog_function(helper, &[], &[x(self.verilog_amount_check(), "a >>> b")])
+            }
+            Helper::LogicalShr => self.verilog_function(
+                helper,
+                &[],
+                &[x(self.verilog_amount_check(), "$unsigned(a) >> b")],
+            ),
+            Helper::Rotl | Helper::Rotr => {
+                let (shift, result) = if helper == Helper::Rotl {
+                    ("w = {a, a} << b;", "$F = w[$DHI:$W];")
+                } else {
+                    ("w = {a, a} >> b;", "$F = w[$HI:0];")
+                };
+                self.verilog_function(
+                    helper,
+                    &["reg [$DHI:0] w;"],
+                    &lines(&[
+                        &format!("if ({})", self.verilog_amount_check()),
+                        "    $F = $X;",
+                        "else begin",
+                        &format!("    {shift}"),
+                        &format!("    {result}"),
+                        "end",
+                    ]),
+                )
+            }
+            Helper::Popcount => self.verilog_function(
+                helper,
+                &["integer i;"],
+                &lines(&[
+                    "$F = 0;",
+                    "for (i = 0; i < $W; i = i + 1)",
+                    "    $F = $F + a[i];",
+                ]),
+            ),
+            Helper::Clz | Helper::Ctz => {
+                let range = if helper == Helper::Clz {
+                    "for (i = $HI; i >= 0; i = i - 1)"
+                } else {
+                    "for (i = 0; i < $W; i = i + 1)"
+                };
+                self.verilog_function(
+                    helper,
+                    &["integer i;", "reg found;"],
+                    &lines(&[
+                        "$F = 0;",
+                        "found = 0;",
+                        range,
+                        "    if (!found) begin",
+                        "        if (a[i])",
+                        "            found = 1;",
+                        "        else",
+                        "            $F = $F + 1;",
+                        "    end",
+                    ]),
+                )
+            }
+            Helper::Bswap => {
+                let bytes: Vec<_> = (0..self.bits / 8)
+                    .map(|byte| format!("a[{}:{}]", byte * 8 + 7, byte * 8))
+                    .collect();
+                self.verilog_function(helper, &[], &[format!("$F = {{{}}};", bytes.join(", "))])
+            }
+            Helper::Bitrev => self.verilog_function(
+                helper,
+                &["integer i;"],
+                &lines(&["for (i = 0; i < $W; i = i + 1)", "    $F[i] = a[$HI - i];"]),
+            ),
+            Helper::Abs => {
+                self.verilog_function(helper, &[], &[x("a == $MIN", "(a < 0) ? -a : a")])
+            }
+            Helper::Min | Helper::Max => {
+                let op = if helper == Helper::Min { '<' } else { '>' };
+                self.verilog_function(helper, &[], &[format!("$F = (a {op} b) ? a : b;")])
+            }
+            _ => unreachable!("not used by the Verilog backend"),
+        }
+    }
+}
+
+fn lines(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+// エスケープなしの表示可能な ASCII 1 文字なら、その文字をそのまま書ける
+fn plain_char(text: &str) -> Option<char> {
+    let inner = text.strip_prefix('\'')?.strip_suffix('\'')?;
+    let mut chars = inner.chars();
+    let ch = chars.next()?;
+    (chars.next().is_none() && (' '..='~').contains(&ch) && ch != '\'' && ch != '\\').then_some(ch)
+}
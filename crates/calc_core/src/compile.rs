@@ -0,0 +1,131 @@
+use crate::ast::{BinaryOp, Expr, UnaryOp};
+use crate::eval::Evaluator;
+use crate::int::Int;
+use crate::{
+    parse_ast_with, BigInt, CalcResult, Context, ErrorKind, EvalOptions, Precision, Signedness,
+    Span, Value,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Instr {
+    Literal(BigInt),
+    NegatedLiteral(BigInt),
+    Load(usize),
+    Unary(UnaryOp),
+    Binary(BinaryOp),
+    Call(String, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledExpr {
+    code: Vec<(Instr, Span)>,
+    variables: Vec<String>,
+}
+
+impl CompiledExpr {
+    pub fn new(input: &str) -> CalcResult<Self> {
+        CompiledExpr::new_with(input, &EvalOptions::default())
+    }
+
+    // 評価はスタックで行うので、options は解析時の上限にだけ使う
+    pub fn new_with(input: &str, options: &EvalOptions) -> CalcResult<Self> {
+        Ok(CompiledExpr::from_expr(&parse_ast_with(input, options)?))
+    }
+
+    pub fn from_expr(expr: &Expr) -> Self {
+        let mut compiled = CompiledExpr {
+            code: Vec::new(),
+            variables: Vec::new(),
+        };
+        compiled.compile(expr);
+        compiled
+    }
+
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn eval(&self, options: &EvalOptions) -> CalcResult<Value> {
+        self.eval_in(&Context::with_options(*options))
+    }
+
+    pub fn eval_in(&self, context: &Context) -> CalcResult<Value> {
+        self.dispatch(context, None)
+    }
+
+    // values は variables() と同じ順に並べる
+    pub fn eval_with(&self, options: &EvalOptions, values: &[Value]) -> CalcResult<Value> {
+        self.dispatch(&Context::with_options(*options), Some(values))
+    }
+
+    fn dispatch(&self, context: &Context, values: Option<&[Value]>) -> CalcResult<Value> {
+        let options = context.options();
+        match (options.precision, options.signedness) {
+            (Precision::Arbitrary, _) => self.run::<BigInt>(context, values),
+            (Precision::Fixed, Signedness::Signed) => self.run::<i128>(context, values),
+            (Precision::Fixed, Signedness::Unsigned) => self.run::<u128>(context, values),
+        }
+    }
+
+    fn run<T: Int>(&self, context: &Context, values: Option<&[Value]>) -> CalcResult<Value> {
+        let evaluator = Evaluator::<T>::new(context);
+        // 変数の誤りは読み込む命令の位置で報告するので、ここでは結果だけ求めておく
+        let variables: Vec<_> = self
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(slot, name)| match values {
+                Some(values) => values
+                    .get(slot)
+                    .ok_or_else(|| ErrorKind::UnknownIdentifier(name.clone()))
+                    .and_then(|value| evaluator.bind(name, value)),
+                None => evaluator.variable(name),
+            })
+            .collect();
+        evaluator.run(&self.code, &variables)
+    }
+
+    fn compile(&mut self, expr: &Expr) {
+        let instr = match expr {
+            Expr::Literal(literal) => Instr::Literal(literal.value.clone()),
+            Expr::Variable { name, .. } => Instr::Load(self.slot(name)),
+            Expr::Call { name, args, .. } => {
+                for arg in args {
+                    self.compile(arg);
+                }
+                Instr::Call(name.clone(), args.len())
+            }
+            Expr::Unary { op, operand, .. } => match (op, &**operand) {
+                (UnaryOp::Neg, Expr::Literal(literal)) => {
+                    Instr::NegatedLiteral(literal.value.clone())
+                }
+                (op, operand) => {
+                    self.compile(operand);
+                    Instr::Unary(*op)
+                }
+            },
+            Expr::Binary { .. } => {
+                // 左結合の連鎖は再帰せずに並べる
+                let (first, links) = expr.left_chain();
+                self.compile(first);
+                for (node, op, rhs) in links {
+                    self.compile(rhs);
+                    self.code.push((Instr::Binary(op), node.span()));
+                }
+                return;
+            }
+            Expr::Paren { inner, .. } => return self.compile(inner),
+        };
+        self.code.push((instr, expr.span()));
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        match self.variables.iter().position(|known| known == name) {
+            Some(slot) => slot,
+            None => {
+                self.variables.push(name.to_string());
+                self.variables.len() - 1
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use crate::{EvalOptions, Value};
+
+pub(crate) const ANS: &str = "ans";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Context {
+    options: EvalOptions,
+    variables: HashMap<String, Value>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Context::default()
+    }
+
+    pub fn with_options(options: EvalOptions) -> Self {
+        Context {
+            options,
+            variables: HashMap::new(),
+        }
+    }
+
+    pub fn options(&self) -> &EvalOptions {
+        &self.options
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.variables.get(name).cloned()
+    }
+
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.variables.insert(name.into(), value.into());
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.variables.remove(name)
+    }
+
+    pub fn ans(&self) -> Option<Value> {
+        self.get(ANS)
+    }
+}
@@ -1,22 +1,212 @@
 use std::fmt;
 
+use crate::{BinaryOp, Span, UnaryOp, Value};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum CalcError {
+pub enum ErrorKind {
     InvalidToken(char),
     InvalidLiteral,
     LiteralOutOfRange,
-    RangeError,
+    DivisionByZero,
+    RemainderByZero,
+    Overflow {
+        op: Operation,
+        operands: Vec<Value>,
+    },
+    ResultOutOfRange(Value),
+    VariableOutOfRange(String),
+    InvalidShiftAmount,
+    ShiftOverflow,
+    NegativeExponent,
+    InvalidRadix,
+    UnknownIdentifier(String),
+    UnknownFunction(String),
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    RequiresFixedWidth(String),
+    NestingTooDeep(usize),
+    ExpressionTooLong(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Unary(UnaryOp),
+    Binary(BinaryOp),
+    Call(String),
+}
+
+impl From<UnaryOp> for Operation {
+    fn from(op: UnaryOp) -> Self {
+        Operation::Unary(op)
+    }
+}
+
+impl From<BinaryOp> for Operation {
+    fn from(op: BinaryOp) -> Self {
+        Operation::Binary(op)
+    }
+}
+
+impl Operation {
+    pub fn name(&self) -> String {
+        let name = match self {
+            Operation::Unary(UnaryOp::Neg) => "negation",
+            Operation::Unary(UnaryOp::Not) => "bitwise not",
+            Operation::Binary(BinaryOp::Add) => "addition",
+            Operation::Binary(BinaryOp::Sub) => "subtraction",
+            Operation::Binary(BinaryOp::Mul) => "multiplication",
+            Operation::Binary(BinaryOp::Div) => "division",
+            Operation::Binary(BinaryOp::Rem) => "remainder",
+            Operation::Binary(BinaryOp::Pow) => "exponentiation",
+            Operation::Binary(BinaryOp::BitAnd) => "bitwise and",
+            Operation::Binary(BinaryOp::BitOr) => "bitwise or",
+            Operation::Binary(BinaryOp::BitXor) => "bitwise xor",
+            Operation::Binary(BinaryOp::Shl) => "left shift",
+            Operation::Binary(BinaryOp::Shr) => "right shift",
+            Operation::Binary(BinaryOp::LogicalShr) => "logical right shift",
+            Operation::Binary(BinaryOp::Rotl) => "rotate left",
+            Operation::Binary(BinaryOp::Rotr) => "rotate right",
+            Operation::Call(name) => return format!("function '{name}'"),
+        };
+        name.to_string()
+    }
+
+    fn write_call(&self, f: &mut fmt::Formatter<'_>, operands: &[Value]) -> fmt::Result {
+        match (self, operands) {
+            (Operation::Unary(op), [value]) if value.is_negative() => {
+                write!(f, "{}({value})", op.symbol())
+            }
+            (Operation::Unary(op), [value]) => write!(f, "{}{value}", op.symbol()),
+            (Operation::Binary(op), [lhs, rhs]) => write!(f, "{lhs} {} {rhs}", op.symbol()),
+            _ => {
+                let name = match self {
+                    Operation::Unary(op) => op.symbol(),
+                    Operation::Binary(op) => op.symbol(),
+                    Operation::Call(name) => name,
+                };
+                write!(f, "{name}(")?;
+                for (i, value) in operands.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalcError {
+    kind: ErrorKind,
+    span: Option<Span>,
 }
 
 pub type CalcResult<T> = Result<T, CalcError>;
 
+impl CalcError {
+    pub fn new(kind: ErrorKind, span: Span) -> Self {
+        CalcError {
+            kind,
+            span: Some(span),
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn render(&self, input: &str) -> String {
+        let Some(span) = self.span else {
+            return self.to_string();
+        };
+        let start = span.start.min(input.len());
+        let end = span.end.clamp(start, input.len());
+        let (Some(before), Some(marked)) = (input.get(..start), input.get(start..end)) else {
+            return self.to_string();
+        };
+
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = input[start..]
+            .find('\n')
+            .map_or(input.len(), |idx| start + idx);
+        // タブはそのまま残して桁をそろえる
+        let indent: String = before[line_start..]
+            .chars()
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let marked = marked.lines().next().unwrap_or("");
+        let carets = "^".repeat(marked.chars().count().max(1));
+        format!("{}\n{indent}{carets} {self}", &input[line_start..line_end])
+    }
+}
+
+impl From<ErrorKind> for CalcError {
+    fn from(kind: ErrorKind) -> Self {
+        CalcError { kind, span: None }
+    }
+}
+
 impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CalcError::InvalidToken(ch) => write!(f, "invalid token '{ch}'"),
-            CalcError::InvalidLiteral => write!(f, "invalid literal"),
-            CalcError::LiteralOutOfRange => write!(f, "literal out of range"),
-            CalcError::RangeError => write!(f, "range error"),
+            ErrorKind::InvalidToken(ch) => write!(f, "invalid token '{ch}'"),
+            ErrorKind::InvalidLiteral => write!(f, "invalid literal"),
+            ErrorKind::LiteralOutOfRange => write!(f, "literal out of range"),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::RemainderByZero => write!(f, "remainder by zero"),
+            ErrorKind::Overflow { op, operands } => {
+                write!(f, "overflow in {}: ", op.name())?;
+                op.write_call(f, operands)?;
+                write!(f, " is out of range")
+            }
+            ErrorKind::ResultOutOfRange(value) => {
+                write!(f, "result {value} is out of range")
+            }
+            ErrorKind::VariableOutOfRange(name) => {
+                write!(f, "value of '{name}' does not fit the current width")
+            }
+            ErrorKind::InvalidShiftAmount => write!(f, "shift amount out of range"),
+            ErrorKind::ShiftOverflow => write!(f, "shift overflow"),
+            ErrorKind::NegativeExponent => write!(f, "negative exponent"),
+            ErrorKind::InvalidRadix => write!(f, "radix must be between 2 and 36"),
+            ErrorKind::UnknownIdentifier(name) => write!(f, "unknown identifier '{name}'"),
+            ErrorKind::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            ErrorKind::ArityMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "function '{name}' expects {expected} argument(s) but got {found}"
+            ),
+            ErrorKind::RequiresFixedWidth(name) => {
+                write!(f, "'{name}' requires a fixed integer width")
+            }
+            ErrorKind::NestingTooDeep(limit) => {
+                write!(f, "expression is nested too deeply (limit {limit})")
+            }
+            ErrorKind::ExpressionTooLong(limit) => {
+                write!(f, "expression is too long (limit {limit} tokens)")
+            }
         }
     }
 }
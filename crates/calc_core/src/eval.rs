@@ -0,0 +1,314 @@
+use std::marker::PhantomData;
+
+use crate::ast::{BinaryOp, Expr, UnaryOp};
+use crate::compile::Instr;
+use crate::int::{check_range, BitOp, Int};
+use crate::{
+    builtins, BigInt, CalcError, CalcResult, Context, ErrorKind, Operation, OverflowPolicy,
+    Precision, Span, Value, Width,
+};
+
+pub(crate) struct Evaluator<'c, T> {
+    context: &'c Context,
+    width: Width,
+    overflow: OverflowPolicy,
+    marker: PhantomData<T>,
+}
+
+impl<'c, T: Int> Evaluator<'c, T> {
+    pub(crate) fn new(context: &'c Context) -> Self {
+        Evaluator {
+            context,
+            width: context.options().width,
+            overflow: match context.options().precision {
+                Precision::Fixed => context.options().overflow,
+                Precision::Arbitrary => OverflowPolicy::Checked,
+            },
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn eval_value(&self, expr: &Expr) -> CalcResult<Value> {
+        Ok(self.eval(expr)?.to_value())
+    }
+
+    fn eval(&self, expr: &Expr) -> CalcResult<T> {
+        let value = match expr {
+            Expr::Literal(literal) => self.literal_value(&literal.value),
+            Expr::Variable { name, .. } => self.variable(name),
+            Expr::Call { name, args, .. } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<CalcResult<Vec<_>>>()?;
+                self.call(name, &args)
+            }
+            Expr::Unary { op, operand, .. } => match (op, &**operand) {
+                (UnaryOp::Neg, Expr::Literal(literal)) => self.negated_literal(&literal.value),
+                (op, operand) => {
+                    let value = self.eval(operand)?;
+                    self.apply_unary(*op, value)
+                }
+            },
+            Expr::Binary { .. } => return self.eval_chain(expr),
+            Expr::Paren { inner, .. } => return self.eval(inner),
+        };
+        value.map_err(|kind| CalcError::new(kind, expr.span()))
+    }
+
+    // 左結合の連鎖は再帰せず左から順に畳み込む
+    fn eval_chain(&self, expr: &Expr) -> CalcResult<T> {
+        let (first, links) = expr.left_chain();
+        let mut acc = self.eval(first)?;
+        for (node, op, rhs) in links {
+            let rhs = self.eval(rhs)?;
+            acc = self
+                .apply_binary(op, acc, rhs)
+                .map_err(|kind| CalcError::new(kind, node.span()))?;
+        }
+        Ok(acc)
+    }
+
+    pub(crate) fn run(
+        &self,
+        code: &[(Instr, Span)],
+        variables: &[Result<T, ErrorKind>],
+    ) -> CalcResult<Value> {
+        let mut stack = Vec::new();
+        for (instr, span) in code {
+            let value = match instr {
+                Instr::Literal(magnitude) => self.literal_value(magnitude),
+                Instr::NegatedLiteral(magnitude) => self.negated_literal(magnitude),
+                Instr::Load(slot) => variables[*slot].clone(),
+                Instr::Unary(op) => {
+                    let value = pop(&mut stack);
+                    self.apply_unary(*op, value)
+                }
+                Instr::Binary(op) => {
+                    let rhs = pop(&mut stack);
+                    let lhs = pop(&mut stack);
+                    self.apply_binary(*op, lhs, rhs)
+                }
+                Instr::Call(name, argc) => {
+                    let args = stack.split_off(stack.len() - argc);
+                    self.call(name, &args)
+                }
+            };
+            stack.push(value.map_err(|kind| CalcError::new(kind, *span))?);
+        }
+        Ok(pop(&mut stack).to_value())
+    }
+
+    pub(crate) fn variable(&self, name: &str) -> Result<T, ErrorKind> {
+        let value = self
+            .context
+            .get(name)
+            .ok_or_else(|| ErrorKind::UnknownIdentifier(name.to_string()))?;
+        self.bind(name, &value)
+    }
+
+    pub(crate) fn bind(&self, name: &str, value: &Value) -> Result<T, ErrorKind> {
+        T::from_value(value)
+            .and_then(|value| check_range(value, self.width))
+            .ok_or_else(|| ErrorKind::VariableOutOfRange(name.to_string()))
+    }
+
+    pub(crate) fn call(&self, name: &str, args: &[T]) -> Result<T, ErrorKind> {
+        let builtin =
+            builtins::lookup(name).ok_or_else(|| ErrorKind::UnknownFunction(name.to_string()))?;
+        builtin.call(args, self.width)
+    }
+
+    pub(crate) fn apply_unary(&self, op: UnaryOp, value: T) -> Result<T, ErrorKind> {
+        match op {
+            UnaryOp::Neg => self.negate(value),
+            UnaryOp::Not => Ok(value.bit_not(self.width)),
+        }
+    }
+
+    pub(crate) fn apply_binary(&self, op: BinaryOp, lhs: T, rhs: T) -> Result<T, ErrorKind> {
+        match op {
+            BinaryOp::Add => self.apply_overflow(
+                op,
+                &[&lhs, &rhs],
+                lhs.checked_add(&rhs),
+                || lhs.wrapping_add(&rhs),
+                rhs.is_negative(),
+            ),
+            BinaryOp::Sub => self.apply_overflow(
+                op,
+                &[&lhs, &rhs],
+                lhs.checked_sub(&rhs),
+                || lhs.wrapping_sub(&rhs),
+                !rhs.is_negative(),
+            ),
+            BinaryOp::Mul => self.apply_overflow(
+                op,
+                &[&lhs, &rhs],
+                lhs.checked_mul(&rhs),
+                || lhs.wrapping_mul(&rhs),
+                lhs.is_negative() != rhs.is_negative(),
+            ),
+            BinaryOp::Div => {
+                if rhs == T::zero() {
+                    return Err(ErrorKind::DivisionByZero);
+                }
+                self.apply_overflow(
+                    op,
+                    &[&lhs, &rhs],
+                    lhs.checked_div_euclid(&rhs),
+                    || lhs.wrapping_div_euclid(&rhs),
+                    lhs.is_negative() != rhs.is_negative(),
+                )
+            }
+            BinaryOp::Rem => {
+                if rhs == T::zero() {
+                    return Err(ErrorKind::RemainderByZero);
+                }
+                lhs.checked_rem_euclid(&rhs)
+                    .and_then(|rem| check_range(rem, self.width))
+                    .ok_or_else(|| overflow(op, &[&lhs, &rhs]))
+            }
+            BinaryOp::Pow => {
+                if rhs.is_negative() {
+                    return Err(ErrorKind::NegativeExponent);
+                }
+                let exponent = match rhs.to_u32() {
+                    Some(exponent) => exponent,
+                    // 底が 0, 1, -1 なら結果は指数の偶奇だけで決まるので、同じ偶奇の小さい指数で計算する
+                    None if lhs.checked_abs().is_some_and(|abs| abs <= T::from_u32(1)) => {
+                        match rhs.checked_rem_euclid(&T::from_u32(2)) {
+                            Some(parity) if parity == T::zero() => 2,
+                            _ => 3,
+                        }
+                    }
+                    None => return Err(overflow(op, &[&lhs, &rhs])),
+                };
+                self.apply_overflow(
+                    op,
+                    &[&lhs, &rhs],
+                    lhs.checked_pow(exponent),
+                    || lhs.wrapping_pow(exponent),
+                    lhs.is_negative() && exponent % 2 == 1,
+                )
+            }
+            BinaryOp::BitAnd => Ok(lhs.bitwise(&rhs, BitOp::And, self.width)),
+            BinaryOp::BitOr => Ok(lhs.bitwise(&rhs, BitOp::Or, self.width)),
+            BinaryOp::BitXor => Ok(lhs.bitwise(&rhs, BitOp::Xor, self.width)),
+            BinaryOp::Shl
+            | BinaryOp::Shr
+            | BinaryOp::LogicalShr
+            | BinaryOp::Rotl
+            | BinaryOp::Rotr => apply_shift(op, &lhs, &rhs, self.width),
+        }
+    }
+
+    fn negate(&self, value: T) -> Result<T, ErrorKind> {
+        self.apply_overflow(
+            UnaryOp::Neg,
+            &[&value],
+            value.checked_neg(),
+            || value.wrapping_neg(),
+            !value.is_negative(),
+        )
+    }
+
+    pub(crate) fn literal_value(&self, magnitude: &BigInt) -> Result<T, ErrorKind> {
+        let value = T::from_magnitude(magnitude).filter(|value| value.in_range(self.width));
+        match self.overflow {
+            OverflowPolicy::Checked => value.ok_or(ErrorKind::LiteralOutOfRange),
+            OverflowPolicy::Wrapping => Ok(T::from_bits(magnitude.low_u128(), self.width)),
+            OverflowPolicy::Saturating => Ok(value.unwrap_or(T::saturated(false, self.width))),
+        }
+    }
+
+    pub(crate) fn negated_literal(&self, magnitude: &BigInt) -> Result<T, ErrorKind> {
+        let value = T::from_negated_magnitude(magnitude).filter(|value| value.in_range(self.width));
+        match self.overflow {
+            OverflowPolicy::Checked if *magnitude > T::negated_literal_limit(self.width) => {
+                Err(ErrorKind::LiteralOutOfRange)
+            }
+            OverflowPolicy::Checked => value.ok_or_else(|| ErrorKind::Overflow {
+                op: Operation::Unary(UnaryOp::Neg),
+                operands: vec![Value::from(magnitude.clone())],
+            }),
+            OverflowPolicy::Wrapping => Ok(T::from_bits(
+                magnitude.low_u128().wrapping_neg(),
+                self.width,
+            )),
+            OverflowPolicy::Saturating => Ok(value.unwrap_or(T::saturated(true, self.width))),
+        }
+    }
+
+    fn apply_overflow(
+        &self,
+        op: impl Into<Operation>,
+        operands: &[&T],
+        checked: Option<T>,
+        wrapped: impl FnOnce() -> T,
+        negative: bool,
+    ) -> Result<T, ErrorKind> {
+        let in_range = checked.filter(|value| value.in_range(self.width));
+        match self.overflow {
+            OverflowPolicy::Checked => in_range.ok_or_else(|| overflow(op, operands)),
+            OverflowPolicy::Wrapping => {
+                let wrapped = wrapped();
+                Ok(match wrapped.to_bits(self.width) {
+                    Some(bits) => T::from_bits(bits, self.width),
+                    None => wrapped,
+                })
+            }
+            OverflowPolicy::Saturating => {
+                Ok(in_range.unwrap_or_else(|| T::saturated(negative, self.width)))
+            }
+        }
+    }
+}
+
+fn pop<T>(stack: &mut Vec<T>) -> T {
+    stack
+        .pop()
+        .expect("compiled code keeps operands on the stack")
+}
+
+fn overflow<T: Int>(op: impl Into<Operation>, operands: &[&T]) -> ErrorKind {
+    ErrorKind::Overflow {
+        op: op.into(),
+        operands: operands.iter().map(|value| value.to_value()).collect(),
+    }
+}
+
+fn apply_shift<T: Int>(op: BinaryOp, value: &T, amount: &T, width: Width) -> Result<T, ErrorKind> {
+    let amount = amount
+        .to_u32()
+        .filter(|amount| u64::from(*amount) < T::shift_limit(width))
+        .ok_or(ErrorKind::InvalidShiftAmount)?;
+    if op == BinaryOp::Shl {
+        let shifted = value.shl_exact(amount).ok_or(ErrorKind::ShiftOverflow)?;
+        return check_range(shifted, width).ok_or(ErrorKind::ShiftOverflow);
+    }
+    if op == BinaryOp::Shr {
+        return check_range(value.shr(amount), width).ok_or(ErrorKind::ShiftOverflow);
+    }
+
+    let bits = width.bits();
+    let pattern = value
+        .to_bits(width)
+        .ok_or_else(|| ErrorKind::RequiresFixedWidth(op.symbol().to_string()))?;
+    match op {
+        BinaryOp::LogicalShr => Ok(T::from_bits(pattern >> amount, width)),
+        BinaryOp::Rotl => Ok(T::from_bits(rotate_left(pattern, amount, width), width)),
+        BinaryOp::Rotr => {
+            let amount = (bits - amount) % bits;
+            Ok(T::from_bits(rotate_left(pattern, amount, width), width))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn rotate_left(pattern: u128, amount: u32, width: Width) -> u128 {
+    if amount == 0 {
+        return pattern;
+    }
+    ((pattern << amount) | (pattern >> (width.bits() - amount))) & width.mask()
+}
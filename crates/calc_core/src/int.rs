@@ -0,0 +1,409 @@
+use std::fmt;
+
+use crate::{BigInt, Value, Width};
+
+pub(crate) const BIG_MAX_BITS: u64 = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BitOp {
+    And,
+    Or,
+    Xor,
+}
+
+impl BitOp {
+    fn apply<N>(self, lhs: N, rhs: N) -> N
+    where
+        N: std::ops::BitAnd<Output = N>
+            + std::ops::BitOr<Output = N>
+            + std::ops::BitXor<Output = N>,
+    {
+        match self {
+            BitOp::And => lhs & rhs,
+            BitOp::Or => lhs | rhs,
+            BitOp::Xor => lhs ^ rhs,
+        }
+    }
+}
+
+pub(crate) trait Int: Clone + Ord + fmt::Debug {
+    fn zero() -> Self;
+    fn from_u32(value: u32) -> Self;
+    fn to_u32(&self) -> Option<u32>;
+    fn is_negative(&self) -> bool;
+    fn in_range(&self, width: Width) -> bool;
+    fn saturated(negative: bool, width: Width) -> Self;
+    fn literal_limit(width: Width) -> BigInt;
+    fn negated_literal_limit(width: Width) -> BigInt;
+    fn from_magnitude(magnitude: &BigInt) -> Option<Self>;
+    fn from_negated_magnitude(magnitude: &BigInt) -> Option<Self>;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    fn checked_neg(&self) -> Option<Self>;
+    fn checked_div_euclid(&self, rhs: &Self) -> Option<Self>;
+    fn checked_rem_euclid(&self, rhs: &Self) -> Option<Self>;
+    fn checked_pow(&self, exponent: u32) -> Option<Self>;
+    fn checked_abs(&self) -> Option<Self>;
+    fn wrapping_add(&self, rhs: &Self) -> Self;
+    fn wrapping_sub(&self, rhs: &Self) -> Self;
+    fn wrapping_mul(&self, rhs: &Self) -> Self;
+    fn wrapping_neg(&self) -> Self;
+    fn wrapping_div_euclid(&self, rhs: &Self) -> Self;
+    fn wrapping_pow(&self, exponent: u32) -> Self;
+    fn bitwise(&self, rhs: &Self, op: BitOp, width: Width) -> Self;
+    fn bit_not(&self, width: Width) -> Self;
+    fn shift_limit(width: Width) -> u64;
+    fn shl_exact(&self, amount: u32) -> Option<Self>;
+    fn shr(&self, amount: u32) -> Self;
+    fn to_bits(&self, width: Width) -> Option<u128>;
+    fn from_bits(bits: u128, width: Width) -> Self;
+    fn to_value(&self) -> Value;
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+macro_rules! impl_fixed_int_common {
+    ($ty:ident) => {
+        fn zero() -> Self {
+            0
+        }
+
+        fn from_u32(value: u32) -> Self {
+            $ty::from(value)
+        }
+
+        fn to_u32(&self) -> Option<u32> {
+            u32::try_from(*self).ok()
+        }
+
+        fn in_range(&self, width: Width) -> bool {
+            (Self::saturated(true, width)..=Self::saturated(false, width)).contains(self)
+        }
+
+        fn checked_add(&self, rhs: &Self) -> Option<Self> {
+            $ty::checked_add(*self, *rhs)
+        }
+
+        fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+            $ty::checked_sub(*self, *rhs)
+        }
+
+        fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+            $ty::checked_mul(*self, *rhs)
+        }
+
+        fn checked_neg(&self) -> Option<Self> {
+            $ty::checked_neg(*self)
+        }
+
+        fn checked_div_euclid(&self, rhs: &Self) -> Option<Self> {
+            $ty::checked_div_euclid(*self, *rhs)
+        }
+
+        fn checked_rem_euclid(&self, rhs: &Self) -> Option<Self> {
+            $ty::checked_rem_euclid(*self, *rhs)
+        }
+
+        fn checked_pow(&self, exponent: u32) -> Option<Self> {
+            $ty::checked_pow(*self, exponent)
+        }
+
+        fn wrapping_add(&self, rhs: &Self) -> Self {
+            $ty::wrapping_add(*self, *rhs)
+        }
+
+        fn wrapping_sub(&self, rhs: &Self) -> Self {
+            $ty::wrapping_sub(*self, *rhs)
+        }
+
+        fn wrapping_mul(&self, rhs: &Self) -> Self {
+            $ty::wrapping_mul(*self, *rhs)
+        }
+
+        fn wrapping_neg(&self) -> Self {
+            $ty::wrapping_neg(*self)
+        }
+
+        fn wrapping_div_euclid(&self, rhs: &Self) -> Self {
+            $ty::wrapping_div_euclid(*self, *rhs)
+        }
+
+        fn wrapping_pow(&self, exponent: u32) -> Self {
+            $ty::wrapping_pow(*self, exponent)
+        }
+
+        fn bitwise(&self, rhs: &Self, op: BitOp, width: Width) -> Self {
+            let bits = op.apply(fixed_bits(self, width), fixed_bits(rhs, width));
+            Self::from_bits(bits, width)
+        }
+
+        fn bit_not(&self, width: Width) -> Self {
+            Self::from_bits(!fixed_bits(self, width), width)
+        }
+
+        fn shift_limit(width: Width) -> u64 {
+            u64::from(width.bits())
+        }
+
+        fn shl_exact(&self, amount: u32) -> Option<Self> {
+            let shifted = *self << amount;
+            (shifted >> amount == *self).then_some(shifted)
+        }
+
+        fn shr(&self, amount: u32) -> Self {
+            *self >> amount
+        }
+    };
+}
+
+fn fixed_bits<T: Int>(value: &T, width: Width) -> u128 {
+    value.to_bits(width).unwrap_or_default()
+}
+
+impl Int for i128 {
+    impl_fixed_int_common!(i128);
+
+    fn is_negative(&self) -> bool {
+        *self < 0
+    }
+
+    fn saturated(negative: bool, width: Width) -> Self {
+        if negative {
+            width.min_value()
+        } else {
+            width.max_value()
+        }
+    }
+
+    fn literal_limit(width: Width) -> BigInt {
+        BigInt::from(width.max_value())
+    }
+
+    fn negated_literal_limit(width: Width) -> BigInt {
+        BigInt::from(width.min_value()).neg()
+    }
+
+    fn from_magnitude(magnitude: &BigInt) -> Option<Self> {
+        magnitude.to_i128()
+    }
+
+    fn from_negated_magnitude(magnitude: &BigInt) -> Option<Self> {
+        magnitude.neg().to_i128()
+    }
+
+    fn checked_abs(&self) -> Option<Self> {
+        i128::checked_abs(*self)
+    }
+
+    fn to_bits(&self, width: Width) -> Option<u128> {
+        Some((*self as u128) & width.unsigned_max_value())
+    }
+
+    fn from_bits(bits: u128, width: Width) -> Self {
+        let unused = 128 - width.bits();
+        ((bits << unused) as i128) >> unused
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Signed(*self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_i128()
+    }
+}
+
+impl Int for u128 {
+    impl_fixed_int_common!(u128);
+
+    fn is_negative(&self) -> bool {
+        false
+    }
+
+    fn saturated(negative: bool, width: Width) -> Self {
+        if negative {
+            0
+        } else {
+            width.unsigned_max_value()
+        }
+    }
+
+    fn literal_limit(width: Width) -> BigInt {
+        BigInt::from(width.unsigned_max_value())
+    }
+
+    fn negated_literal_limit(width: Width) -> BigInt {
+        BigInt::from(width.unsigned_max_value())
+    }
+
+    fn from_magnitude(magnitude: &BigInt) -> Option<Self> {
+        magnitude.to_u128()
+    }
+
+    fn from_negated_magnitude(magnitude: &BigInt) -> Option<Self> {
+        magnitude.is_zero().then_some(0)
+    }
+
+    fn checked_abs(&self) -> Option<Self> {
+        Some(*self)
+    }
+
+    fn to_bits(&self, width: Width) -> Option<u128> {
+        Some(*self & width.unsigned_max_value())
+    }
+
+    fn from_bits(bits: u128, width: Width) -> Self {
+        bits & width.unsigned_max_value()
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Unsigned(*self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_u128()
+    }
+}
+
+impl Int for BigInt {
+    fn zero() -> Self {
+        BigInt::zero()
+    }
+
+    fn from_u32(value: u32) -> Self {
+        BigInt::from(value)
+    }
+
+    fn to_u32(&self) -> Option<u32> {
+        self.to_u128().and_then(|value| u32::try_from(value).ok())
+    }
+
+    fn is_negative(&self) -> bool {
+        BigInt::is_negative(self)
+    }
+
+    fn in_range(&self, _width: Width) -> bool {
+        self.bit_len() <= BIG_MAX_BITS
+    }
+
+    fn saturated(negative: bool, _width: Width) -> Self {
+        let limit = BigInt::ones(BIG_MAX_BITS);
+        if negative {
+            limit.neg()
+        } else {
+            limit
+        }
+    }
+
+    fn literal_limit(_width: Width) -> BigInt {
+        BigInt::ones(BIG_MAX_BITS)
+    }
+
+    fn negated_literal_limit(_width: Width) -> BigInt {
+        BigInt::ones(BIG_MAX_BITS)
+    }
+
+    fn from_magnitude(magnitude: &BigInt) -> Option<Self> {
+        Some(magnitude.clone())
+    }
+
+    fn from_negated_magnitude(magnitude: &BigInt) -> Option<Self> {
+        Some(magnitude.neg())
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self.add(rhs))
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(BigInt::sub(self, rhs))
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self.mul(rhs))
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(self.neg())
+    }
+
+    fn checked_div_euclid(&self, rhs: &Self) -> Option<Self> {
+        self.div_rem_euclid(rhs).map(|(quot, _)| quot)
+    }
+
+    fn checked_rem_euclid(&self, rhs: &Self) -> Option<Self> {
+        self.div_rem_euclid(rhs).map(|(_, rem)| rem)
+    }
+
+    fn checked_pow(&self, exponent: u32) -> Option<Self> {
+        self.pow(exponent, BIG_MAX_BITS)
+    }
+
+    fn checked_abs(&self) -> Option<Self> {
+        Some(self.abs())
+    }
+
+    fn wrapping_add(&self, rhs: &Self) -> Self {
+        self.add(rhs)
+    }
+
+    fn wrapping_sub(&self, rhs: &Self) -> Self {
+        BigInt::sub(self, rhs)
+    }
+
+    fn wrapping_mul(&self, rhs: &Self) -> Self {
+        self.mul(rhs)
+    }
+
+    fn wrapping_neg(&self) -> Self {
+        self.neg()
+    }
+
+    fn wrapping_div_euclid(&self, rhs: &Self) -> Self {
+        self.checked_div_euclid(rhs).unwrap_or_default()
+    }
+
+    fn wrapping_pow(&self, exponent: u32) -> Self {
+        self.checked_pow(exponent).unwrap_or_default()
+    }
+
+    fn bitwise(&self, rhs: &Self, op: BitOp, _width: Width) -> Self {
+        BigInt::bitwise(self, rhs, |a, b| op.apply(a, b))
+    }
+
+    fn bit_not(&self, _width: Width) -> Self {
+        self.not()
+    }
+
+    fn shift_limit(_width: Width) -> u64 {
+        BIG_MAX_BITS
+    }
+
+    fn shl_exact(&self, amount: u32) -> Option<Self> {
+        Some(self.shl(amount))
+    }
+
+    fn shr(&self, amount: u32) -> Self {
+        BigInt::shr(self, amount)
+    }
+
+    fn to_bits(&self, _width: Width) -> Option<u128> {
+        None
+    }
+
+    fn from_bits(bits: u128, _width: Width) -> Self {
+        BigInt::from(bits)
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Big(self.clone())
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(value.to_big())
+    }
+}
+
+pub(crate) fn check_range<T: Int>(value: T, width: Width) -> Option<T> {
+    Some(value).filter(|value| value.in_range(width))
+}
@@ -1,402 +1,299 @@
+mod ast;
+mod bigint;
+mod builtins;
+mod codegen;
+mod compile;
+mod context;
 mod error;
-
-pub use crate::error::{CalcError, CalcResult};
-
-const MIN_I32: i64 = i32::MIN as i64;
-const MAX_I32: i64 = i32::MAX as i64;
-const MAX_I32_PLUS_ONE: i64 = (i32::MAX as i64) + 1;
+mod eval;
+mod int;
+mod literal;
+mod options;
+mod parser;
+mod pretty;
+mod token;
+mod trace;
+mod value;
+
+pub use crate::ast::{BinaryOp, Expr, Literal, PartialAst, UnaryOp};
+pub use crate::bigint::BigInt;
+pub use crate::codegen::{GeneratedCode, Language};
+pub use crate::compile::CompiledExpr;
+pub use crate::context::Context;
+pub use crate::error::{CalcError, CalcResult, ErrorKind, Operation};
+pub use crate::options::{EvalOptions, OverflowPolicy, Precision, Signedness, Width};
+pub use crate::pretty::PrettyOptions;
+pub use crate::token::{tokenize, LiteralBase, Operator, Span, Token, TokenKind};
+pub use crate::trace::{TraceStep, MAX_TRACE_STEPS};
+pub use crate::value::Value;
+
+use crate::context::ANS;
+use crate::int::Int;
+use crate::literal::parse_literal;
+use crate::parser::{hex_minus_error, significant_tokens};
+
+const MIN_RADIX: u32 = 2;
+const MAX_RADIX: u32 = 36;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FormattedValue {
     pub bin: String,
+    pub oct: String,
     pub dec: String,
     pub hex: String,
+    pub bin_twos: String,
+    pub oct_twos: String,
+    pub hex_twos: String,
 }
 
-pub fn format_all(value: i64) -> CalcResult<FormattedValue> {
-    let value = check_range(value)?;
-    Ok(FormattedValue {
-        bin: format_binary(value),
-        dec: value.to_string(),
-        hex: format_hex_string(value),
-    })
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum NegativeStyle {
+    #[default]
+    SignMagnitude,
+    TwosComplement,
 }
 
-pub fn parse(input: &str) -> CalcResult<i64> {
-    let bytes = input.as_bytes();
-    let mut idx = 0;
-
-    skip_ws(bytes, &mut idx);
-    let start = idx;
-    let value = parse_literal(input, bytes, &mut idx)?;
-    skip_ws(bytes, &mut idx);
-    if idx < bytes.len() {
-        let ch = input[idx..].chars().next().unwrap_or('\0');
-        if ch == '-' && is_hex_prefix(bytes, start) {
-            return Err(CalcError::LiteralOutOfRange);
-        }
-        return Err(CalcError::InvalidToken(ch));
-    }
-    Ok(value)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub prefix: bool,
+    pub uppercase: bool,
+    pub group: Option<usize>,
+    pub negative: NegativeStyle,
 }
 
-pub fn eval_expression(input: &str) -> CalcResult<i64> {
-    let bytes = input.as_bytes();
-    let mut idx = 0;
-
-    let value = parse_expression(input, bytes, &mut idx)?;
-    skip_ws(bytes, &mut idx);
-    if idx < bytes.len() {
-        let ch = input[idx..].chars().next().unwrap_or('\0');
-        return Err(CalcError::InvalidToken(ch));
-    }
-    Ok(value)
-}
-
-fn parse_expression(input: &str, bytes: &[u8], idx: &mut usize) -> CalcResult<i64> {
-    let mut acc = parse_term(input, bytes, idx)?;
-    acc = check_range(acc)?;
-
-    loop {
-        skip_ws(bytes, idx);
-        if *idx >= bytes.len() {
-            break;
-        }
-
-        match bytes[*idx] {
-            b'+' => {
-                *idx += 1;
-                ensure_operand_after_operator('+', bytes, idx)?;
-                let rhs = parse_term(input, bytes, idx)?;
-                let sum = acc.checked_add(rhs).ok_or(CalcError::RangeError)?;
-                acc = check_range(sum)?;
-            }
-            b'-' => {
-                *idx += 1;
-                ensure_operand_after_operator('-', bytes, idx)?;
-                let rhs = parse_term(input, bytes, idx)?;
-                let diff = acc.checked_sub(rhs).ok_or(CalcError::RangeError)?;
-                acc = check_range(diff)?;
-            }
-            _ => break,
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            prefix: true,
+            uppercase: true,
+            group: None,
+            negative: NegativeStyle::SignMagnitude,
         }
     }
-
-    Ok(acc)
 }
 
-fn parse_term(input: &str, bytes: &[u8], idx: &mut usize) -> CalcResult<i64> {
-    let mut acc = parse_factor(input, bytes, idx)?;
-    acc = check_range(acc)?;
-
-    loop {
-        skip_ws(bytes, idx);
-        if *idx >= bytes.len() {
-            break;
-        }
-
-        match bytes[*idx] {
-            b'*' => {
-                *idx += 1;
-                ensure_operand_after_operator('*', bytes, idx)?;
-                let rhs = parse_factor(input, bytes, idx)?;
-                let product = acc.checked_mul(rhs).ok_or(CalcError::RangeError)?;
-                acc = check_range(product)?;
-            }
-            b'%' => {
-                *idx += 1;
-                ensure_operand_after_operator('%', bytes, idx)?;
-                let rhs = parse_factor(input, bytes, idx)?;
-                if rhs == 0 {
-                    return Err(CalcError::RangeError);
-                }
-                let rem = acc.rem_euclid(rhs);
-                acc = check_range(rem)?;
-            }
-            _ => break,
-        }
-    }
+pub fn format_all(value: i64) -> CalcResult<FormattedValue> {
+    format_all_with(value, &EvalOptions::default())
+}
 
-    Ok(acc)
+pub fn format_all_with(
+    value: impl Into<Value>,
+    options: &EvalOptions,
+) -> CalcResult<FormattedValue> {
+    let value = options.check(value.into())?;
+    let plain = FormatOptions::default();
+    let grouped = FormatOptions {
+        group: Some(4),
+        ..plain
+    };
+    let twos = FormatOptions {
+        negative: NegativeStyle::TwosComplement,
+        ..plain
+    };
+    let grouped_twos = FormatOptions {
+        negative: NegativeStyle::TwosComplement,
+        ..grouped
+    };
+    Ok(FormattedValue {
+        bin: format_value(&value, 2, &grouped, options),
+        oct: format_value(&value, 8, &plain, options),
+        dec: format_value(&value, 10, &plain, options),
+        hex: format_value(&value, 16, &plain, options),
+        bin_twos: format_value(&value, 2, &grouped_twos, options),
+        oct_twos: format_value(&value, 8, &twos, options),
+        hex_twos: format_value(&value, 16, &twos, options),
+    })
 }
 
-fn parse_factor(input: &str, bytes: &[u8], idx: &mut usize) -> CalcResult<i64> {
-    parse_factor_with_literal_max(input, bytes, idx, MAX_I32)
+pub fn format_radix(value: i64, radix: u32, options: &FormatOptions) -> CalcResult<String> {
+    format_radix_with(value, radix, options, &EvalOptions::default())
 }
 
-fn parse_factor_with_literal_max(
-    input: &str,
-    bytes: &[u8],
-    idx: &mut usize,
-    literal_max: i64,
-) -> CalcResult<i64> {
-    skip_ws(bytes, idx);
-    if *idx >= bytes.len() {
-        return Err(CalcError::InvalidLiteral);
+pub fn format_radix_with(
+    value: impl Into<Value>,
+    radix: u32,
+    format: &FormatOptions,
+    options: &EvalOptions,
+) -> CalcResult<String> {
+    if !(MIN_RADIX..=MAX_RADIX).contains(&radix) {
+        return Err(ErrorKind::InvalidRadix.into());
     }
-    if *idx < bytes.len() && bytes[*idx] == b'-' {
-        *idx += 1;
-        ensure_operand_after_operator('-', bytes, idx)?;
-        let value = parse_factor_with_literal_max(input, bytes, idx, MAX_I32_PLUS_ONE)?;
-        let negated = value.checked_neg().ok_or(CalcError::RangeError)?;
-        return check_range(negated);
+    let value = options.check(value.into())?;
+    Ok(format_value(&value, radix, format, options))
+}
+
+pub fn parse(input: &str) -> CalcResult<i64> {
+    let tokens = significant_tokens(input);
+    let Some(literal) = tokens.first() else {
+        let end = Span::new(input.len(), input.len());
+        return Err(CalcError::new(ErrorKind::InvalidLiteral, end));
+    };
+    let text = literal.text(input);
+    if !matches!(literal.kind, TokenKind::Literal(_)) {
+        let ch = text.chars().next().unwrap_or('\0');
+        return Err(CalcError::new(ErrorKind::InvalidToken(ch), literal.span));
     }
-    if bytes[*idx] == b'(' {
-        *idx += 1;
-        skip_ws(bytes, idx);
-        if *idx >= bytes.len() {
-            return Err(CalcError::InvalidToken(')'));
+    let width = EvalOptions::default().width;
+    let limit = i128::literal_limit(width);
+    let value =
+        parse_literal(text, Some(&limit)).map_err(|kind| CalcError::new(kind, literal.span))?;
+    if let Some(token) = tokens.get(1) {
+        if let Some(err) = hex_minus_error(literal, token) {
+            return Err(err);
         }
-        if bytes[*idx] == b')' {
-            return Err(CalcError::InvalidToken(')'));
+        // 空白を挟んで続く `-` は範囲外として扱う（これまでの parse と同じ）
+        if literal.kind == TokenKind::Literal(LiteralBase::Hex)
+            && token.kind == TokenKind::Operator(Operator::Minus)
+        {
+            let span = Span::new(literal.span.start, token.span.end);
+            return Err(CalcError::new(ErrorKind::LiteralOutOfRange, span));
         }
-        let value = parse_expression(input, bytes, idx)?;
-        skip_ws(bytes, idx);
-        if *idx >= bytes.len() || bytes[*idx] != b')' {
-            return Err(CalcError::InvalidToken(')'));
-        }
-        *idx += 1;
-        return Ok(value);
+        let ch = token.text(input).chars().next().unwrap_or('\0');
+        return Err(CalcError::new(ErrorKind::InvalidToken(ch), token.span));
     }
-    parse_literal_with_max(input, bytes, idx, literal_max)
+    value
+        .to_i128()
+        .and_then(|value| i64::try_from(value).ok())
+        .ok_or_else(|| CalcError::new(ErrorKind::LiteralOutOfRange, literal.span))
 }
 
-fn ensure_operand_after_operator(op: char, bytes: &[u8], idx: &mut usize) -> CalcResult<()> {
-    skip_ws(bytes, idx);
-    if *idx >= bytes.len() {
-        return Err(CalcError::InvalidToken(op));
-    }
-    Ok(())
+pub fn parse_ast(input: &str) -> CalcResult<Expr> {
+    parse_ast_with(input, &EvalOptions::default())
 }
 
-fn parse_literal(input: &str, bytes: &[u8], idx: &mut usize) -> CalcResult<i64> {
-    parse_literal_with_max(input, bytes, idx, MAX_I32)
+// 入れ子の深さと長さの上限だけを使う
+pub fn parse_ast_with(input: &str, options: &EvalOptions) -> CalcResult<Expr> {
+    parser::parse_expression(input, options)
 }
 
-fn parse_literal_with_max(
-    input: &str,
-    bytes: &[u8],
-    idx: &mut usize,
-    literal_max: i64,
-) -> CalcResult<i64> {
-    skip_ws(bytes, idx);
-    if *idx >= bytes.len() {
-        return Err(CalcError::InvalidLiteral);
-    }
-
-    let value = if bytes[*idx] == b'0' && matches!(bytes.get(*idx + 1), Some(b'b') | Some(b'B')) {
-        *idx += 2;
-        let start = *idx;
-        while *idx < bytes.len() {
-            let b = bytes[*idx];
-            if b == b'0' || b == b'1' || b == b'_' {
-                *idx += 1;
-            } else if b.is_ascii_alphanumeric() {
-                return Err(CalcError::InvalidLiteral);
-            } else {
-                break;
-            }
-        }
-
-        if *idx == start {
-            return Err(CalcError::InvalidLiteral);
-        }
-
-        let token = &input[start..*idx];
-        parse_binary(token, literal_max)?
-    } else if bytes[*idx] == b'0' && matches!(bytes.get(*idx + 1), Some(b'x') | Some(b'X')) {
-        *idx += 2;
-        let start = *idx;
-        while *idx < bytes.len() {
-            let b = bytes[*idx];
-            if b.is_ascii_hexdigit() || b == b'_' {
-                *idx += 1;
-            } else if b == b'-' || b.is_ascii_alphanumeric() {
-                return Err(CalcError::InvalidLiteral);
-            } else {
-                break;
-            }
-        }
-
-        if *idx == start {
-            return Err(CalcError::InvalidLiteral);
-        }
-
-        let token = &input[start..*idx];
-        parse_hex(token, literal_max)?
-    } else {
-        let start = *idx;
-        while *idx < bytes.len() {
-            let b = bytes[*idx];
-            if b.is_ascii_digit() || b == b'_' {
-                *idx += 1;
-            } else {
-                break;
-            }
-        }
-
-        if *idx == start {
-            let ch = input[*idx..].chars().next().unwrap_or('\0');
-            return Err(CalcError::InvalidToken(ch));
-        }
+pub fn parse_ast_recovering(input: &str) -> PartialAst {
+    parse_ast_recovering_with(input, &EvalOptions::default())
+}
 
-        let token = &input[start..*idx];
-        parse_decimal(token, literal_max)?
-    };
-    if value > literal_max {
-        return Err(CalcError::LiteralOutOfRange);
-    }
-    Ok(value)
+pub fn parse_ast_recovering_with(input: &str, options: &EvalOptions) -> PartialAst {
+    parser::parse_recovering(input, options)
 }
 
-fn parse_decimal(token: &str, literal_max: i64) -> CalcResult<i64> {
-    let mut value: i64 = 0;
-    let mut seen_digit = false;
-    let mut prev_underscore = false;
-
-    for ch in token.chars() {
-        if ch == '_' {
-            if !seen_digit || prev_underscore {
-                return Err(CalcError::InvalidLiteral);
-            }
-            prev_underscore = true;
-            continue;
-        }
+pub fn format_expr(expr: &Expr, options: &PrettyOptions) -> String {
+    pretty::format_expr(expr, options)
+}
 
-        let digit = ch.to_digit(10).ok_or(CalcError::InvalidLiteral)? as i64;
-        seen_digit = true;
-        prev_underscore = false;
+pub fn pretty_print(input: &str, options: &PrettyOptions) -> CalcResult<String> {
+    pretty_print_with(input, options, &EvalOptions::default())
+}
 
-        value = value
-            .checked_mul(10)
-            .and_then(|v| v.checked_add(digit))
-            .ok_or(CalcError::LiteralOutOfRange)?;
+pub fn pretty_print_with(
+    input: &str,
+    options: &PrettyOptions,
+    limits: &EvalOptions,
+) -> CalcResult<String> {
+    Ok(format_expr(&parse_ast_with(input, limits)?, options))
+}
 
-        if value > literal_max {
-            return Err(CalcError::LiteralOutOfRange);
-        }
-    }
+pub fn codegen(
+    input: &str,
+    language: Language,
+    options: &EvalOptions,
+) -> CalcResult<GeneratedCode> {
+    let expr = parser::parse_expression(input, options)?;
+    codegen_expr(&expr, language, options)
+}
 
-    if !seen_digit || prev_underscore {
-        return Err(CalcError::InvalidLiteral);
-    }
+pub fn codegen_expr(
+    expr: &Expr,
+    language: Language,
+    options: &EvalOptions,
+) -> CalcResult<GeneratedCode> {
+    codegen::generate(expr, language, options)
+}
 
-    Ok(value)
+pub fn eval_expression(input: &str) -> CalcResult<i64> {
+    let value = eval_in(&mut Context::new(), input)?;
+    value
+        .as_i128()
+        .and_then(|value| i64::try_from(value).ok())
+        .ok_or_else(|| {
+            CalcError::new(
+                ErrorKind::ResultOutOfRange(value),
+                Span::new(0, input.len()),
+            )
+        })
 }
 
-fn parse_binary(token: &str, literal_max: i64) -> CalcResult<i64> {
-    let mut value: i64 = 0;
-    let mut seen_digit = false;
-    let mut prev_underscore = false;
-
-    for ch in token.chars() {
-        if ch == '_' {
-            if !seen_digit || prev_underscore {
-                return Err(CalcError::InvalidLiteral);
-            }
-            prev_underscore = true;
-            continue;
-        }
+pub fn eval_with(input: &str, options: &EvalOptions) -> CalcResult<Value> {
+    eval_in(&mut Context::with_options(*options), input)
+}
 
-        let digit = match ch {
-            '0' => 0,
-            '1' => 1,
-            _ => return Err(CalcError::InvalidLiteral),
-        };
-        seen_digit = true;
-        prev_underscore = false;
-
-        value = value
-            .checked_mul(2)
-            .and_then(|v| v.checked_add(digit))
-            .ok_or(CalcError::LiteralOutOfRange)?;
-
-        if value > literal_max {
-            return Err(CalcError::LiteralOutOfRange);
-        }
-    }
+pub fn eval_traced(input: &str, options: &EvalOptions) -> CalcResult<Vec<TraceStep>> {
+    eval_traced_in(&Context::with_options(*options), input)
+}
 
-    if !seen_digit || prev_underscore {
-        return Err(CalcError::InvalidLiteral);
-    }
+pub fn eval_traced_in(context: &Context, input: &str) -> CalcResult<Vec<TraceStep>> {
+    trace::eval_traced(context, input)
+}
 
+pub fn eval_in(context: &mut Context, input: &str) -> CalcResult<Value> {
+    let (target, expr) = parser::parse_statement(input, context.options())?;
+    let value = expr.eval_in(context)?;
+    assign(context, target, &value);
     Ok(value)
 }
 
-fn parse_hex(token: &str, literal_max: i64) -> CalcResult<i64> {
-    let mut value: i64 = 0;
-    let mut seen_digit = false;
-    let mut prev_underscore = false;
-
-    for ch in token.chars() {
-        if ch == '_' {
-            if !seen_digit || prev_underscore {
-                return Err(CalcError::InvalidLiteral);
-            }
-            prev_underscore = true;
-            continue;
-        }
-
-        let digit = ch.to_digit(16).ok_or(CalcError::InvalidLiteral)? as i64;
-        seen_digit = true;
-        prev_underscore = false;
-
-        value = value
-            .checked_mul(16)
-            .and_then(|v| v.checked_add(digit))
-            .ok_or(CalcError::LiteralOutOfRange)?;
-
-        if value > literal_max {
-            return Err(CalcError::LiteralOutOfRange);
-        }
-    }
+// eval_in と同じ評価をしながら過程も記録する（解析・評価は 1 回だけ）
+pub fn eval_in_traced(context: &mut Context, input: &str) -> CalcResult<(Value, Vec<TraceStep>)> {
+    let (target, expr) = parser::parse_statement(input, context.options())?;
+    let (value, steps) = trace::trace(context, input, &expr)?;
+    assign(context, target, &value);
+    Ok((value, steps))
+}
 
-    if !seen_digit || prev_underscore {
-        return Err(CalcError::InvalidLiteral);
+fn assign(context: &mut Context, target: Option<&str>, value: &Value) {
+    if let Some(name) = target {
+        context.set(name, value.clone());
     }
-
-    Ok(value)
+    context.set(ANS, value.clone());
 }
 
-fn check_range(value: i64) -> CalcResult<i64> {
-    if value < MIN_I32 || value > MAX_I32 {
-        Err(CalcError::RangeError)
-    } else {
-        Ok(value)
+fn format_value(
+    value: &Value,
+    radix: u32,
+    format: &FormatOptions,
+    options: &EvalOptions,
+) -> String {
+    let value = value.to_big();
+    match (format.negative, options.precision) {
+        (NegativeStyle::TwosComplement, Precision::Fixed) if value.is_negative() => {
+            let bits = value.low_u128() & options.width.unsigned_max_value();
+            format_digits(&BigInt::from(bits), radix, format)
+        }
+        _ => format_digits(&value, radix, format),
     }
 }
 
-fn format_binary(value: i64) -> String {
-    let (sign, mut n) = if value < 0 {
-        ("-", -value)
+fn format_digits(value: &BigInt, radix: u32, options: &FormatOptions) -> String {
+    let sign = if value.is_negative() { "-" } else { "" };
+    let prefix = if options.prefix {
+        radix_prefix(radix)
     } else {
-        ("", value)
+        String::new()
     };
 
-    if n == 0 {
-        return format!("{sign}0b0");
-    }
-
-    let mut bits = String::new();
-    while n > 0 {
-        bits.push(if n & 1 == 1 { '1' } else { '0' });
-        n >>= 1;
-    }
-    let bits: String = bits.chars().rev().collect();
-    let grouped = group_from_right(&bits, 4, '_');
-    format!("{sign}0b{grouped}")
+    let digits = value.magnitude_digits(radix, options.uppercase);
+    let digits = match options.group {
+        Some(group) if group > 0 => group_from_right(&digits, group, '_'),
+        _ => digits,
+    };
+    format!("{sign}{prefix}{digits}")
 }
 
-fn format_hex_string(value: i64) -> String {
-    let (sign, n) = if value < 0 {
-        ("-", -value)
-    } else {
-        ("", value)
-    };
-    if n == 0 {
-        return format!("{sign}0x0");
+fn radix_prefix(radix: u32) -> String {
+    match radix {
+        2 => "0b".to_string(),
+        8 => "0o".to_string(),
+        10 => String::new(),
+        16 => "0x".to_string(),
+        _ => format!("{radix}r"),
     }
-    format!("{sign}0x{:X}", n)
 }
 
 fn group_from_right(input: &str, group: usize, sep: char) -> String {
@@ -416,16 +313,3 @@ fn group_from_right(input: &str, group: usize, sep: char) -> String {
     }
     out.chars().rev().collect()
 }
-
-fn is_hex_prefix(bytes: &[u8], start: usize) -> bool {
-    bytes.get(start) == Some(&b'0') && matches!(bytes.get(start + 1), Some(b'x') | Some(b'X'))
-}
-
-fn skip_ws(bytes: &[u8], idx: &mut usize) {
-    while *idx < bytes.len() {
-        match bytes[*idx] {
-            b' ' | b'\t' | b'\n' | b'\r' => *idx += 1,
-            _ => break,
-        }
-    }
-}
@@ -0,0 +1,158 @@
+use crate::{BigInt, ErrorKind, MAX_RADIX, MIN_RADIX};
+
+pub(crate) fn parse_literal(token: &str, literal_max: Option<&BigInt>) -> Result<BigInt, ErrorKind> {
+    let value = if let Some(rest) = token.strip_prefix('\'') {
+        parse_char_literal(rest)?
+    } else if let Some(rest) = token.strip_prefix("b\"") {
+        parse_byte_string(rest, literal_max)?
+    } else if let Some(radix) = prefixed_radix(token.as_bytes()) {
+        parse_digits(radix_digits(&token[2..], radix)?, radix, literal_max)?
+    } else {
+        match token.split_once(['r', 'R']) {
+            Some((radix, digits)) => {
+                let radix = parse_radix(radix)?;
+                parse_digits(radix_digits(digits, radix)?, radix, literal_max)?
+            }
+            None => parse_digits(token, 10, literal_max)?,
+        }
+    };
+    check_literal(value, literal_max)
+}
+
+fn check_literal(value: BigInt, literal_max: Option<&BigInt>) -> Result<BigInt, ErrorKind> {
+    match literal_max {
+        Some(max) if value > *max => Err(ErrorKind::LiteralOutOfRange),
+        _ => Ok(value),
+    }
+}
+
+fn parse_char_literal(input: &str) -> Result<BigInt, ErrorKind> {
+    let mut idx = 0;
+    let value = next_quoted_char(input, &mut idx, '\'')?.ok_or(ErrorKind::InvalidLiteral)?;
+    if next_quoted_char(input, &mut idx, '\'')?.is_some() || idx < input.len() {
+        return Err(ErrorKind::InvalidLiteral);
+    }
+    Ok(BigInt::from(value))
+}
+
+fn parse_byte_string(input: &str, literal_max: Option<&BigInt>) -> Result<BigInt, ErrorKind> {
+    let mut idx = 0;
+    let mut packed = Vec::new();
+    loop {
+        if input.as_bytes().get(idx).is_some_and(|b| !b.is_ascii()) {
+            return Err(ErrorKind::InvalidLiteral);
+        }
+        match next_quoted_char(input, &mut idx, '"')? {
+            Some(value) if value <= 0xFF => packed.push(value as u8),
+            Some(_) => return Err(ErrorKind::InvalidLiteral),
+            None => break,
+        }
+    }
+    if packed.is_empty() {
+        return Err(ErrorKind::InvalidLiteral);
+    }
+
+    match &input[idx..] {
+        "" | "be" => {}
+        "le" => packed.reverse(),
+        _ => return Err(ErrorKind::InvalidLiteral),
+    }
+
+    let mut value = BigInt::zero();
+    for byte in packed {
+        value.mul_small_add(256, u32::from(byte));
+        value = check_literal(value, literal_max)?;
+    }
+    Ok(value)
+}
+
+fn next_quoted_char(input: &str, idx: &mut usize, quote: char) -> Result<Option<u32>, ErrorKind> {
+    let mut chars = input[*idx..].chars();
+    let ch = chars.next().ok_or(ErrorKind::InvalidLiteral)?;
+    *idx += ch.len_utf8();
+    if ch == quote {
+        return Ok(None);
+    }
+    if ch != '\\' {
+        return Ok(Some(u32::from(ch)));
+    }
+
+    let escape = chars.next().ok_or(ErrorKind::InvalidLiteral)?;
+    *idx += escape.len_utf8();
+    let value = match escape {
+        'n' => 0x0A,
+        'r' => 0x0D,
+        't' => 0x09,
+        '0' => 0x00,
+        '\\' | '\'' | '"' => u32::from(escape),
+        'x' => {
+            let hex = input.get(*idx..*idx + 2).ok_or(ErrorKind::InvalidLiteral)?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ErrorKind::InvalidLiteral);
+            }
+            *idx += 2;
+            u32::from_str_radix(hex, 16).map_err(|_| ErrorKind::InvalidLiteral)?
+        }
+        _ => return Err(ErrorKind::InvalidLiteral),
+    };
+    Ok(Some(value))
+}
+
+fn prefixed_radix(bytes: &[u8]) -> Option<u32> {
+    if bytes.first() != Some(&b'0') {
+        return None;
+    }
+    match bytes.get(1) {
+        Some(b'b') | Some(b'B') => Some(2),
+        Some(b'o') | Some(b'O') => Some(8),
+        Some(b'x') | Some(b'X') => Some(16),
+        _ => None,
+    }
+}
+
+fn parse_radix(token: &str) -> Result<u32, ErrorKind> {
+    if token.contains('_') {
+        return Err(ErrorKind::InvalidLiteral);
+    }
+    match token.parse::<u32>() {
+        Ok(radix) if (MIN_RADIX..=MAX_RADIX).contains(&radix) => Ok(radix),
+        _ => Err(ErrorKind::InvalidRadix),
+    }
+}
+
+fn radix_digits(token: &str, radix: u32) -> Result<&str, ErrorKind> {
+    if token.is_empty() || !token.chars().all(|ch| ch.is_digit(radix) || ch == '_') {
+        return Err(ErrorKind::InvalidLiteral);
+    }
+    Ok(token)
+}
+
+fn parse_digits(token: &str, radix: u32, literal_max: Option<&BigInt>) -> Result<BigInt, ErrorKind> {
+    let mut value = BigInt::zero();
+    let mut seen_digit = false;
+    let mut prev_underscore = false;
+
+    for ch in token.chars() {
+        if ch == '_' {
+            if !seen_digit || prev_underscore {
+                return Err(ErrorKind::InvalidLiteral);
+            }
+            prev_underscore = true;
+            continue;
+        }
+
+        let digit = ch.to_digit(radix).ok_or(ErrorKind::InvalidLiteral)?;
+        seen_digit = true;
+        prev_underscore = false;
+
+        value.mul_small_add(radix, digit);
+        value = check_literal(value, literal_max)?;
+    }
+
+    if !seen_digit || prev_underscore {
+        return Err(ErrorKind::InvalidLiteral);
+    }
+
+    Ok(value)
+}
+
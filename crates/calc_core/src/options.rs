@@ -0,0 +1,105 @@
+use crate::{ErrorKind, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Width {
+    W8,
+    W16,
+    #[default]
+    W32,
+    W64,
+    W128,
+}
+
+impl Width {
+    pub fn bits(self) -> u32 {
+        match self {
+            Width::W8 => 8,
+            Width::W16 => 16,
+            Width::W32 => 32,
+            Width::W64 => 64,
+            Width::W128 => 128,
+        }
+    }
+
+    pub fn min_value(self) -> i128 {
+        i128::MIN >> (128 - self.bits())
+    }
+
+    pub fn max_value(self) -> i128 {
+        i128::MAX >> (128 - self.bits())
+    }
+
+    pub fn unsigned_max_value(self) -> u128 {
+        self.mask()
+    }
+
+    pub(crate) fn mask(self) -> u128 {
+        u128::MAX >> (128 - self.bits())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Signedness {
+    #[default]
+    Signed,
+    Unsigned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OverflowPolicy {
+    #[default]
+    Checked,
+    Wrapping,
+    Saturating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Precision {
+    #[default]
+    Fixed,
+    Arbitrary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalOptions {
+    pub width: Width,
+    pub signedness: Signedness,
+    pub overflow: OverflowPolicy,
+    pub precision: Precision,
+    // 式の入れ子の深さの上限
+    pub max_depth: usize,
+    // 式の長さ（トークン数）の上限。構文木の節の数と高さもこれを超えない
+    pub max_tokens: usize,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        EvalOptions {
+            width: Width::default(),
+            signedness: Signedness::default(),
+            overflow: OverflowPolicy::default(),
+            precision: Precision::default(),
+            max_depth: 100,
+            max_tokens: 2000,
+        }
+    }
+}
+
+impl EvalOptions {
+    pub(crate) fn check(&self, value: Value) -> Result<Value, ErrorKind> {
+        let in_range = match (self.precision, self.signedness) {
+            (Precision::Arbitrary, _) => true,
+            (Precision::Fixed, Signedness::Signed) => value
+                .as_i128()
+                .is_some_and(|v| (self.width.min_value()..=self.width.max_value()).contains(&v)),
+            (Precision::Fixed, Signedness::Unsigned) => value
+                .as_u128()
+                .is_some_and(|v| v <= self.width.unsigned_max_value()),
+        };
+        if in_range {
+            Ok(value)
+        } else {
+            Err(ErrorKind::ResultOutOfRange(value))
+        }
+    }
+}
@@ -0,0 +1,452 @@
+use crate::ast::{BinaryOp, Expr, Literal, PartialAst, UnaryOp};
+use crate::literal::parse_literal;
+use crate::{
+    builtins, tokenize, CalcError, CalcResult, ErrorKind, EvalOptions, LiteralBase, Operator, Span,
+    Token, TokenKind,
+};
+
+pub(crate) fn parse_statement<'a>(
+    input: &'a str,
+    options: &EvalOptions,
+) -> CalcResult<(Option<&'a str>, Expr)> {
+    let mut parser = Parser::new(input, options);
+    parser.check_length()?;
+    parser.parse_statement()
+}
+
+pub(crate) fn parse_expression(input: &str, options: &EvalOptions) -> CalcResult<Expr> {
+    let mut parser = Parser::new(input, options);
+    parser.check_length()?;
+    let expr = parser.parse_expression()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+pub(crate) fn parse_recovering(input: &str, options: &EvalOptions) -> PartialAst {
+    let mut parser = Parser::new(input, options);
+    if let Err(err) = parser.check_length() {
+        return PartialAst {
+            exprs: Vec::new(),
+            diagnostics: vec![err],
+        };
+    }
+    parser.recovering = true;
+    let parsed = parser.parse_expression();
+    let mut exprs: Vec<_> = parser.recover(parsed).ok().flatten().into_iter().collect();
+    // 式の後に残ったトークンは読み飛ばして続きを別の断片として解析する
+    while let Some(token) = parser.tokens.get(parser.pos).copied() {
+        parser.report(parser.invalid_token(&token));
+        parser.pos += 1;
+        // 直後の二項演算子は読み飛ばした断片に続くものとして扱う
+        if let Some(TokenKind::Operator(op)) = parser.peek() {
+            if !matches!(op, Operator::Minus | Operator::Tilde) {
+                parser.pos += 1;
+            }
+        }
+        if parser.pos < parser.tokens.len() {
+            let parsed = parser.parse_expression();
+            exprs.extend(parser.recover(parsed).ok().flatten());
+        }
+    }
+
+    let mut diagnostics = parser.diagnostics;
+    diagnostics.sort_by_key(|err| err.span().map(|span| span.start));
+    PartialAst { exprs, diagnostics }
+}
+
+pub(crate) fn significant_tokens(input: &str) -> Vec<Token> {
+    tokenize(input)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Whitespace)
+        .collect()
+}
+
+// 16 進リテラルの直後に空白なしで続く `-` はリテラルの一部とみなして不正とする
+pub(crate) fn hex_minus_error(literal: &Token, next: &Token) -> Option<CalcError> {
+    let attached = literal.kind == TokenKind::Literal(LiteralBase::Hex)
+        && next.kind == TokenKind::Operator(Operator::Minus)
+        && next.span.start == literal.span.end;
+    attached.then(|| {
+        let span = Span::new(literal.span.start, next.span.end);
+        CalcError::new(ErrorKind::InvalidLiteral, span)
+    })
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    recovering: bool,
+    diagnostics: Vec<CalcError>,
+    max_depth: usize,
+    max_tokens: usize,
+    // 解析中の入れ子の深さ（同じ優先順位の連鎖は深くならない）
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, options: &EvalOptions) -> Self {
+        Parser {
+            input,
+            tokens: significant_tokens(input),
+            pos: 0,
+            recovering: false,
+            diagnostics: Vec::new(),
+            max_depth: options.max_depth,
+            max_tokens: options.max_tokens,
+            depth: 0,
+        }
+    }
+
+    // 構文木の節はトークンより多くならないので、トークン数で木の大きさと高さを抑える
+    fn check_length(&self) -> CalcResult<()> {
+        match self.tokens.get(self.max_tokens) {
+            Some(token) => Err(CalcError::new(
+                ErrorKind::ExpressionTooLong(self.max_tokens),
+                token.span,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_statement(&mut self) -> CalcResult<(Option<&'a str>, Expr)> {
+        let target = match (self.peek(), self.tokens.get(1).map(|token| token.kind)) {
+            (Some(TokenKind::Identifier), Some(TokenKind::Operator(Operator::Assign))) => {
+                let name = self.tokens[0].text(self.input);
+                self.pos = 1;
+                self.bump_operator()?;
+                Some(name)
+            }
+            _ => None,
+        };
+
+        let expr = self.parse_expression()?;
+        self.expect_end()?;
+        Ok((target, expr))
+    }
+
+    fn expect_end(&self) -> CalcResult<()> {
+        match self.tokens.get(self.pos) {
+            Some(token) => Err(self.invalid_token(token)),
+            None => Ok(()),
+        }
+    }
+
+    fn parse_expression(&mut self) -> CalcResult<Expr> {
+        self.parse_binary(0)
+    }
+
+    // 結合の強さが min_precedence 以上の二項演算子を左結合で読む。
+    // 優先順位ごとに関数を分けると括弧 1 段ごとの再帰が深くなるのでまとめて扱う
+    fn parse_binary(&mut self, min_precedence: u8) -> CalcResult<Expr> {
+        let lhs = self.parse_factor();
+        let mut acc = self.recover(lhs)?;
+        let mut entered = false;
+
+        while let Some((op, precedence)) = self
+            .peek_operator()
+            .and_then(binary_op)
+            .filter(|&(_, precedence)| precedence >= min_precedence)
+        {
+            let span = self.tokens[self.pos].span;
+            let bumped = self.bump_operator();
+            if self.recover(bumped)?.is_none() {
+                break;
+            }
+            // 右辺の中に演算子があれば 1 段深くなる。連鎖が続いても深さは変わらない
+            if min_precedence > 0 && !entered {
+                self.enter(span)?;
+                entered = true;
+            }
+            let rhs = self.parse_binary(precedence + 1);
+            acc = match (acc, self.recover(rhs)?) {
+                (Some(lhs), Some(rhs)) => Some(binary(op, lhs, rhs)),
+                (lhs, rhs) => lhs.or(rhs),
+            };
+        }
+        if entered {
+            self.depth -= 1;
+        }
+
+        acc.ok_or_else(|| self.reported())
+    }
+
+    fn parse_factor(&mut self) -> CalcResult<Expr> {
+        if self.recovering {
+            self.skip_unexpected();
+        }
+        let op = match self.peek() {
+            None => return Err(CalcError::new(ErrorKind::InvalidLiteral, self.end_span())),
+            Some(TokenKind::Operator(Operator::Minus)) => UnaryOp::Neg,
+            Some(TokenKind::Operator(Operator::Tilde)) => UnaryOp::Not,
+            Some(_) => return self.parse_power(),
+        };
+        let op_span = self.bump_operator()?;
+        self.enter(op_span)?;
+        let operand = match (op, self.peek()) {
+            (UnaryOp::Neg, Some(TokenKind::Literal(_))) => self.parse_power(),
+            _ => self.parse_factor(),
+        };
+        self.depth -= 1;
+        let operand = operand?;
+        Ok(Expr::Unary {
+            op,
+            span: Span::new(op_span.start, operand.span().end),
+            operand: Box::new(operand),
+        })
+    }
+
+    fn parse_power(&mut self) -> CalcResult<Expr> {
+        let base = self.parse_primary()?;
+        if self.peek_operator() != Some(Operator::Power) {
+            return Ok(base);
+        }
+        let span = self.tokens[self.pos].span;
+        let bumped = self.bump_operator();
+        if self.recover(bumped)?.is_none() {
+            return Ok(base);
+        }
+        self.enter(span)?;
+        let exponent = self.parse_factor();
+        self.depth -= 1;
+        match self.recover(exponent)? {
+            Some(exponent) => Ok(binary(BinaryOp::Pow, base, exponent)),
+            None => Ok(base),
+        }
+    }
+
+    fn parse_primary(&mut self) -> CalcResult<Expr> {
+        let Some(token) = self.tokens.get(self.pos).copied() else {
+            return Err(CalcError::new(ErrorKind::InvalidLiteral, self.end_span()));
+        };
+        if matches!(token.kind, TokenKind::CloseParen | TokenKind::Comma) {
+            return Err(self.invalid_token(&token));
+        }
+        self.pos += 1;
+        match token.kind {
+            TokenKind::OpenParen => {
+                if matches!(self.peek(), None | Some(TokenKind::CloseParen)) {
+                    let err = self.expected_close_paren();
+                    self.pos = (self.pos + 1).min(self.tokens.len());
+                    return Err(err);
+                }
+                self.enter(token.span)?;
+                let inner = self.parse_expression();
+                self.depth -= 1;
+                let inner = self.recover(inner)?;
+                if self.peek() == Some(TokenKind::CloseParen) {
+                    self.pos += 1;
+                } else {
+                    self.recover::<()>(Err(self.expected_close_paren()))?;
+                }
+                let end = self.tokens[self.pos - 1].span.end;
+                let inner = inner.ok_or_else(|| self.reported())?;
+                Ok(Expr::Paren {
+                    inner: Box::new(inner),
+                    span: Span::new(token.span.start, end),
+                })
+            }
+            TokenKind::Identifier => {
+                let name = token.text(self.input).to_string();
+                if self.peek() == Some(TokenKind::OpenParen) {
+                    return self.parse_call(name, token.span);
+                }
+                Ok(Expr::Variable {
+                    name,
+                    span: token.span,
+                })
+            }
+            TokenKind::Literal(base) => {
+                let text = token.text(self.input);
+                let value =
+                    parse_literal(text, None).map_err(|kind| CalcError::new(kind, token.span))?;
+                if let Some(err) = self
+                    .tokens
+                    .get(self.pos)
+                    .and_then(|next| hex_minus_error(&token, next))
+                {
+                    return Err(err);
+                }
+                Ok(Expr::Literal(Literal {
+                    base,
+                    text: text.to_string(),
+                    value,
+                    span: token.span,
+                }))
+            }
+            _ => Err(self.invalid_token(&token)),
+        }
+    }
+
+    fn parse_call(&mut self, name: String, name_span: Span) -> CalcResult<Expr> {
+        let known = builtins::lookup(&name).is_some();
+        if !known {
+            let err = CalcError::new(ErrorKind::UnknownFunction(name.clone()), name_span);
+            self.recover::<()>(Err(err))?;
+        }
+        self.pos += 1;
+        self.enter(name_span)?;
+
+        let mut args = Vec::new();
+        let mut closed = self.peek() == Some(TokenKind::CloseParen);
+        while !closed {
+            let arg = self.parse_expression();
+            if let Some(arg) = self.recover(arg)? {
+                args.push(arg);
+            }
+            match self.peek() {
+                Some(TokenKind::Comma) => {
+                    let bumped = self.bump_operator();
+                    if self.recover(bumped)?.is_none() {
+                        break;
+                    }
+                }
+                Some(TokenKind::CloseParen) => closed = true,
+                _ => {
+                    self.recover::<()>(Err(self.expected_close_paren()))?;
+                    break;
+                }
+            }
+        }
+        if closed {
+            self.pos += 1;
+        }
+        self.depth -= 1;
+        let end = self.tokens[self.pos - 1].span.end;
+        if !known {
+            return Err(self.reported());
+        }
+
+        Ok(Expr::Call {
+            name,
+            args,
+            span: Span::new(name_span.start, end),
+        })
+    }
+
+    fn skip_unexpected(&mut self) {
+        // 被演算子の位置にある二項演算子や不明な文字は読み飛ばす
+        while let Some(token) = self.tokens.get(self.pos).copied() {
+            let unexpected = match token.kind {
+                TokenKind::Operator(op) => !matches!(op, Operator::Minus | Operator::Tilde),
+                TokenKind::Unknown => true,
+                _ => false,
+            };
+            if !unexpected || self.pos + 1 >= self.tokens.len() {
+                break;
+            }
+            self.report(self.invalid_token(&token));
+            self.pos += 1;
+        }
+    }
+
+    fn recover<T>(&mut self, result: CalcResult<T>) -> CalcResult<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            // 深すぎる入力はそれ以上読み進めない
+            Err(err) if self.recovering && !matches!(err.kind(), ErrorKind::NestingTooDeep(_)) => {
+                self.report(err);
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn enter(&mut self, span: Span) -> CalcResult<()> {
+        if self.depth >= self.max_depth {
+            return Err(self.too_deep(span));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn too_deep(&mut self, span: Span) -> CalcError {
+        let err = CalcError::new(ErrorKind::NestingTooDeep(self.max_depth), span);
+        if self.recovering {
+            self.report(err.clone());
+            self.pos = self.tokens.len();
+        }
+        err
+    }
+
+    fn report(&mut self, err: CalcError) {
+        // 下位で記録済みのエラーが伝わってきた場合は重複させない
+        if self.diagnostics.last() != Some(&err) {
+            self.diagnostics.push(err);
+        }
+    }
+
+    fn reported(&self) -> CalcError {
+        self.diagnostics
+            .last()
+            .cloned()
+            .unwrap_or_else(|| CalcError::new(ErrorKind::InvalidLiteral, self.end_span()))
+    }
+
+    fn bump_operator(&mut self) -> CalcResult<Span> {
+        let token = self.tokens[self.pos];
+        self.pos += 1;
+        if self.pos >= self.tokens.len() {
+            return Err(self.invalid_token(&token));
+        }
+        Ok(token.span)
+    }
+
+    fn peek(&self) -> Option<TokenKind> {
+        self.tokens.get(self.pos).map(|token| token.kind)
+    }
+
+    fn peek_operator(&self) -> Option<Operator> {
+        match self.peek() {
+            Some(TokenKind::Operator(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn invalid_token(&self, token: &Token) -> CalcError {
+        let ch = token.text(self.input).chars().next().unwrap_or('\0');
+        CalcError::new(ErrorKind::InvalidToken(ch), token.span)
+    }
+
+    fn expected_close_paren(&self) -> CalcError {
+        let span = self
+            .tokens
+            .get(self.pos)
+            .map_or_else(|| self.end_span(), |token| token.span);
+        CalcError::new(ErrorKind::InvalidToken(')'), span)
+    }
+
+    fn end_span(&self) -> Span {
+        Span::new(self.input.len(), self.input.len())
+    }
+}
+
+// 二項演算子と結合の強さ（大きいほど強い）
+fn binary_op(op: Operator) -> Option<(BinaryOp, u8)> {
+    let op = match op {
+        Operator::Pipe => (BinaryOp::BitOr, 0),
+        Operator::Caret => (BinaryOp::BitXor, 1),
+        Operator::Amp => (BinaryOp::BitAnd, 2),
+        Operator::Shl => (BinaryOp::Shl, 3),
+        Operator::Shr => (BinaryOp::Shr, 3),
+        Operator::LogicalShr => (BinaryOp::LogicalShr, 3),
+        Operator::Rotl => (BinaryOp::Rotl, 3),
+        Operator::Rotr => (BinaryOp::Rotr, 3),
+        Operator::Plus => (BinaryOp::Add, 4),
+        Operator::Minus => (BinaryOp::Sub, 4),
+        Operator::Star => (BinaryOp::Mul, 5),
+        Operator::Slash => (BinaryOp::Div, 5),
+        Operator::Percent => (BinaryOp::Rem, 5),
+        _ => return None,
+    };
+    Some(op)
+}
+
+fn binary(op: BinaryOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        span: Span::new(lhs.span().start, rhs.span().end),
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
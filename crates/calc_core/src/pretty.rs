@@ -0,0 +1,171 @@
+use crate::ast::{BinaryOp, Expr, Literal, UnaryOp};
+use crate::{group_from_right, LiteralBase};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyOptions {
+    pub uppercase: bool,
+    pub group: Option<usize>,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            uppercase: true,
+            group: None,
+        }
+    }
+}
+
+// 結合の強さ（大きいほど強い）
+const UNARY: u8 = 7;
+const POWER: u8 = 8;
+const PRIMARY: u8 = 9;
+
+pub(crate) fn format_expr(expr: &Expr, options: &PrettyOptions) -> String {
+    let mut printer = Printer {
+        options,
+        out: String::new(),
+    };
+    printer.expr(expr, 0);
+    printer.out
+}
+
+struct Printer<'o> {
+    options: &'o PrettyOptions,
+    out: String,
+}
+
+impl Printer<'_> {
+    fn expr(&mut self, expr: &Expr, min: u8) {
+        let expr = strip_parens(expr);
+        if precedence(expr) < min {
+            self.out.push('(');
+            self.expr(expr, 0);
+            self.out.push(')');
+            return;
+        }
+
+        match expr {
+            Expr::Literal(literal) => {
+                let text = self.literal(literal);
+                self.out.push_str(&text);
+            }
+            Expr::Variable { name, .. } => self.out.push_str(name),
+            Expr::Call { name, args, .. } => {
+                self.out.push_str(name);
+                self.out.push('(');
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.expr(arg, 0);
+                }
+                self.out.push(')');
+            }
+            Expr::Unary { op, operand, .. } => {
+                // -(リテラル) と -リテラル は範囲の扱いが違うので括弧を残す
+                let keep_parens = *op == UnaryOp::Neg
+                    && matches!(**operand, Expr::Paren { .. })
+                    && matches!(strip_parens(operand), Expr::Literal(_));
+                self.out.push_str(op.symbol());
+                self.expr(operand, if keep_parens { PRIMARY + 1 } else { UNARY });
+            }
+            // ** は右結合で、底には単項演算子を置けない
+            Expr::Binary {
+                op: BinaryOp::Pow,
+                lhs,
+                rhs,
+                ..
+            } => {
+                self.expr(lhs, PRIMARY);
+                self.binary_op(BinaryOp::Pow);
+                self.expr(rhs, UNARY);
+            }
+            Expr::Binary { op, .. } => {
+                // 同じ優先順位の左結合の連鎖は再帰せずに並べる
+                let prec = binary_precedence(*op);
+                let mut links = Vec::new();
+                let mut first = expr;
+                while let Expr::Binary { op, lhs, rhs, .. } = first {
+                    if binary_precedence(*op) != prec {
+                        break;
+                    }
+                    links.push((*op, rhs));
+                    first = strip_parens(lhs);
+                }
+                self.expr(first, prec);
+                for (op, rhs) in links.into_iter().rev() {
+                    self.binary_op(op);
+                    self.expr(rhs, prec + 1);
+                }
+            }
+            Expr::Paren { .. } => unreachable!(),
+        }
+    }
+
+    fn binary_op(&mut self, op: BinaryOp) {
+        self.out.push(' ');
+        self.out.push_str(op.symbol());
+        self.out.push(' ');
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        let text = literal.text.as_str();
+        let (prefix, digits) = match literal.base {
+            LiteralBase::Binary | LiteralBase::Octal | LiteralBase::Hex => {
+                (text[..2].to_ascii_lowercase(), &text[2..])
+            }
+            LiteralBase::Radix(radix) => (
+                format!("{radix}r"),
+                text.split_once(['r', 'R'])
+                    .map_or(text, |(_, digits)| digits),
+            ),
+            LiteralBase::Decimal => (String::new(), text),
+            LiteralBase::Char | LiteralBase::Bytes => return text.to_string(),
+        };
+
+        let digits: String = digits
+            .chars()
+            .filter(|ch| *ch != '_')
+            .map(|ch| {
+                if self.options.uppercase {
+                    ch.to_ascii_uppercase()
+                } else {
+                    ch.to_ascii_lowercase()
+                }
+            })
+            .collect();
+        let digits = match self.options.group {
+            Some(group) if group > 0 => group_from_right(&digits, group, '_'),
+            _ => digits,
+        };
+        format!("{prefix}{digits}")
+    }
+}
+
+fn strip_parens(mut expr: &Expr) -> &Expr {
+    while let Expr::Paren { inner, .. } = expr {
+        expr = inner;
+    }
+    expr
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Binary { op, .. } => binary_precedence(*op),
+        Expr::Unary { .. } => UNARY,
+        _ => PRIMARY,
+    }
+}
+
+fn binary_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::BitOr => 1,
+        BinaryOp::BitXor => 2,
+        BinaryOp::BitAnd => 3,
+        BinaryOp::Shl | BinaryOp::Shr | BinaryOp::LogicalShr | BinaryOp::Rotl | BinaryOp::Rotr => 4,
+        BinaryOp::Add | BinaryOp::Sub => 5,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 6,
+        BinaryOp::Pow => POWER,
+    }
+}
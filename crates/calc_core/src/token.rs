@@ -0,0 +1,212 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LiteralBase {
+    Binary,
+    Octal,
+    Decimal,
+    Hex,
+    Radix(u32),
+    Char,
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Power,
+    Amp,
+    Pipe,
+    Caret,
+    Tilde,
+    Shl,
+    Shr,
+    LogicalShr,
+    Rotl,
+    Rotr,
+    Assign,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Literal(LiteralBase),
+    Identifier,
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+    Comma,
+    Whitespace,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl Token {
+    pub fn text<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.start..self.span.end]
+    }
+}
+
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let start = idx;
+        let kind = scan_token(input, bytes, &mut idx);
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, idx),
+        });
+    }
+    tokens
+}
+
+fn scan_token(input: &str, bytes: &[u8], idx: &mut usize) -> TokenKind {
+    let start = *idx;
+    let rest = &bytes[start..];
+    if let Some(base) = prefixed_base(rest) {
+        *idx += 2;
+        scan_word(bytes, idx);
+        return TokenKind::Literal(base);
+    }
+    match rest[0] {
+        b' ' | b'\t' | b'\n' | b'\r' => {
+            while matches!(bytes.get(*idx), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+                *idx += 1;
+            }
+            TokenKind::Whitespace
+        }
+        b'\'' => {
+            *idx += 1;
+            scan_quoted(input, idx, '\'');
+            TokenKind::Literal(LiteralBase::Char)
+        }
+        b'b' if rest.get(1) == Some(&b'"') => {
+            *idx += 2;
+            scan_quoted(input, idx, '"');
+            scan_word(bytes, idx);
+            TokenKind::Literal(LiteralBase::Bytes)
+        }
+        b'0'..=b'9' | b'_' => {
+            while matches!(bytes.get(*idx), Some(b'0'..=b'9' | b'_')) {
+                *idx += 1;
+            }
+            if !matches!(bytes.get(*idx), Some(b'r' | b'R')) {
+                return TokenKind::Literal(LiteralBase::Decimal);
+            }
+            let radix = input[start..*idx].parse::<u32>().ok();
+            *idx += 1;
+            scan_word(bytes, idx);
+            TokenKind::Literal(radix.map_or(LiteralBase::Decimal, LiteralBase::Radix))
+        }
+        b if b.is_ascii_alphabetic() => {
+            scan_word(bytes, idx);
+            match &input[start..*idx] {
+                "rotl" => TokenKind::Operator(Operator::Rotl),
+                "rotr" => TokenKind::Operator(Operator::Rotr),
+                _ => TokenKind::Identifier,
+            }
+        }
+        b'(' => punct(idx, 1, TokenKind::OpenParen),
+        b')' => punct(idx, 1, TokenKind::CloseParen),
+        b',' => punct(idx, 1, TokenKind::Comma),
+        _ => match match_operator(rest) {
+            Some((op, len)) => punct(idx, len, TokenKind::Operator(op)),
+            None => {
+                *idx += input[start..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Unknown
+            }
+        },
+    }
+}
+
+fn punct(idx: &mut usize, len: usize, kind: TokenKind) -> TokenKind {
+    *idx += len;
+    kind
+}
+
+fn match_operator(rest: &[u8]) -> Option<(Operator, usize)> {
+    const OPERATORS: [(&[u8], Operator); 14] = [
+        (b">>>", Operator::LogicalShr),
+        (b">>", Operator::Shr),
+        (b"<<", Operator::Shl),
+        (b"**", Operator::Power),
+        (b"+", Operator::Plus),
+        (b"-", Operator::Minus),
+        (b"*", Operator::Star),
+        (b"/", Operator::Slash),
+        (b"%", Operator::Percent),
+        (b"&", Operator::Amp),
+        (b"|", Operator::Pipe),
+        (b"^", Operator::Caret),
+        (b"~", Operator::Tilde),
+        (b"=", Operator::Assign),
+    ];
+    OPERATORS
+        .iter()
+        .find(|(symbol, _)| rest.starts_with(symbol))
+        .map(|(symbol, op)| (*op, symbol.len()))
+}
+
+fn prefixed_base(rest: &[u8]) -> Option<LiteralBase> {
+    if rest.first() != Some(&b'0') {
+        return None;
+    }
+    match rest.get(1) {
+        Some(b'b' | b'B') => Some(LiteralBase::Binary),
+        Some(b'o' | b'O') => Some(LiteralBase::Octal),
+        Some(b'x' | b'X') => Some(LiteralBase::Hex),
+        _ => None,
+    }
+}
+
+fn scan_word(bytes: &[u8], idx: &mut usize) {
+    while bytes
+        .get(*idx)
+        .is_some_and(|b| b.is_ascii_alphanumeric() || *b == b'_')
+    {
+        *idx += 1;
+    }
+}
+
+fn scan_quoted(input: &str, idx: &mut usize, quote: char) {
+    let mut chars = input[*idx..].chars();
+    while let Some(ch) = chars.next() {
+        *idx += ch.len_utf8();
+        if ch == quote {
+            break;
+        }
+        if ch == '\\' {
+            if let Some(escaped) = chars.next() {
+                *idx += escaped.len_utf8();
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::ast::{Expr, Literal, UnaryOp};
+use crate::eval::Evaluator;
+use crate::int::Int;
+use crate::{
+    format_all_with, format_digits, parser, BigInt, CalcError, CalcResult, Context, FormatOptions,
+    FormattedValue, LiteralBase, Operation, Precision, Signedness, Span, Value,
+};
+
+// 記録するステップ数の上限（長い式で過程が大きくなりすぎないように）
+pub const MAX_TRACE_STEPS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub span: Span,
+    pub op: Operation,
+    pub operands: Vec<String>,
+    pub value: Value,
+    pub result: FormattedValue,
+}
+
+impl TraceStep {
+    // 部分式の原文（ステップには範囲だけを持たせ、原文は呼び出し側の入力から切り出す）
+    pub fn text<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.start..self.span.end]
+    }
+}
+
+pub(crate) fn eval_traced(context: &Context, input: &str) -> CalcResult<Vec<TraceStep>> {
+    let (_, expr) = parser::parse_statement(input, context.options())?;
+    let (_, steps) = trace(context, input, &expr)?;
+    Ok(steps)
+}
+
+// 値と過程を 1 回の評価で求める
+pub(crate) fn trace(
+    context: &Context,
+    input: &str,
+    expr: &Expr,
+) -> CalcResult<(Value, Vec<TraceStep>)> {
+    let options = context.options();
+    match (options.precision, options.signedness) {
+        (Precision::Arbitrary, _) => Tracer::<BigInt>::new(context, input).run(expr),
+        (Precision::Fixed, Signedness::Signed) => Tracer::<i128>::new(context, input).run(expr),
+        (Precision::Fixed, Signedness::Unsigned) => Tracer::<u128>::new(context, input).run(expr),
+    }
+}
+
+struct Tracer<'a, T> {
+    context: &'a Context,
+    evaluator: Evaluator<'a, T>,
+    input: &'a str,
+    steps: Vec<TraceStep>,
+}
+
+impl<'a, T: Int> Tracer<'a, T> {
+    fn new(context: &'a Context, input: &'a str) -> Self {
+        Tracer {
+            context,
+            evaluator: Evaluator::new(context),
+            input,
+            steps: Vec::new(),
+        }
+    }
+
+    // 上限に達した後は値だけを求め、表示用の文字列は作らない
+    fn full(&self) -> bool {
+        self.steps.len() >= MAX_TRACE_STEPS
+    }
+
+    fn run(mut self, expr: &Expr) -> CalcResult<(Value, Vec<TraceStep>)> {
+        let (value, _) = self.visit(expr)?;
+        Ok((value.to_value(), self.steps))
+    }
+
+    // 部分式の値と、表示に使う基数（リテラル由来のもの）を返す。
+    // 値は表示した被演算子の値から求め、部分式を評価し直さない
+    fn visit(&mut self, expr: &Expr) -> CalcResult<(T, Option<u32>)> {
+        let at = |kind| CalcError::new(kind, expr.span());
+        if let Some(literal) = literal_of(expr) {
+            let value = match expr {
+                Expr::Literal(_) => self.evaluator.literal_value(&literal.value),
+                _ => self.evaluator.negated_literal(&literal.value),
+            };
+            return Ok((value.map_err(at)?, Some(literal_radix(literal.base))));
+        }
+        let (op, operands) = match expr {
+            Expr::Paren { inner, .. } => return self.visit(inner),
+            Expr::Variable { name, .. } => {
+                return Ok((self.evaluator.variable(name).map_err(at)?, None));
+            }
+            Expr::Binary { .. } => return self.visit_chain(expr),
+            Expr::Unary { op, operand, .. } => (Operation::Unary(*op), vec![&**operand]),
+            Expr::Call { name, args, .. } => (Operation::Call(name.clone()), args.iter().collect()),
+            Expr::Literal(_) => unreachable!(),
+        };
+
+        let mut radix = None;
+        let mut values = Vec::new();
+        let mut shown = Vec::new();
+        for operand in operands {
+            let (value, operand_radix) = self.visit(operand)?;
+            radix = radix.or(operand_radix);
+            if !self.full() {
+                shown.push(self.show(operand, &value.to_value(), operand_radix));
+            }
+            values.push(value);
+        }
+
+        let value = match &op {
+            Operation::Call(name) => self.evaluator.call(name, &values),
+            Operation::Unary(op) => {
+                let [value] = take(values);
+                self.evaluator.apply_unary(*op, value)
+            }
+            Operation::Binary(_) => unreachable!(),
+        }
+        .map_err(at)?;
+        self.record(expr, op, shown, &value)?;
+        Ok((value, radix))
+    }
+
+    // 左結合の連鎖は再帰せず、左から 1 段ずつ記録する
+    fn visit_chain(&mut self, expr: &Expr) -> CalcResult<(T, Option<u32>)> {
+        let (first, links) = expr.left_chain();
+        let (mut acc, mut radix) = self.visit(first)?;
+        let mut lhs = first;
+        for (node, op, rhs) in links {
+            let (value, rhs_radix) = self.visit(rhs)?;
+            let shown = if self.full() {
+                Vec::new()
+            } else {
+                vec![
+                    self.show(lhs, &acc.to_value(), radix),
+                    self.show(rhs, &value.to_value(), rhs_radix),
+                ]
+            };
+            radix = radix.or(rhs_radix);
+            acc = self
+                .evaluator
+                .apply_binary(op, acc, value)
+                .map_err(|kind| CalcError::new(kind, node.span()))?;
+            self.record(node, Operation::Binary(op), shown, &acc)?;
+            lhs = node;
+        }
+        Ok((acc, radix))
+    }
+
+    fn record(
+        &mut self,
+        expr: &Expr,
+        op: Operation,
+        operands: Vec<String>,
+        value: &T,
+    ) -> CalcResult<()> {
+        if self.full() {
+            return Ok(());
+        }
+        self.steps.push(TraceStep {
+            span: expr.span(),
+            op,
+            operands,
+            value: value.to_value(),
+            result: format_all_with(value.to_value(), self.context.options())?,
+        });
+        Ok(())
+    }
+
+    // リテラルは書かれたとおり、途中の値はその部分式の基数で表示する
+    fn show(&self, operand: &Expr, value: &Value, radix: Option<u32>) -> String {
+        let mut inner = operand;
+        while let Expr::Paren { inner: next, .. } = inner {
+            inner = next;
+        }
+        if literal_of(inner).is_some() {
+            let span = inner.span();
+            return self.input[span.start..span.end].to_string();
+        }
+        format_digits(
+            &value.to_big(),
+            radix.unwrap_or(10),
+            &FormatOptions::default(),
+        )
+    }
+}
+
+fn take<T, const N: usize>(values: Vec<T>) -> [T; N] {
+    values
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("operand count"))
+}
+
+fn literal_of(expr: &Expr) -> Option<&Literal> {
+    match expr {
+        Expr::Literal(literal) => Some(literal),
+        Expr::Unary {
+            op: UnaryOp::Neg,
+            operand,
+            ..
+        } => match &**operand {
+            Expr::Literal(literal) => Some(literal),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn literal_radix(base: LiteralBase) -> u32 {
+    match base {
+        LiteralBase::Binary => 2,
+        LiteralBase::Octal => 8,
+        LiteralBase::Decimal | LiteralBase::Char => 10,
+        LiteralBase::Hex | LiteralBase::Bytes => 16,
+        LiteralBase::Radix(radix) => radix,
+    }
+}
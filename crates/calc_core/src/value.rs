@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::BigInt;
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Signed(i128),
+    Unsigned(u128),
+    Big(BigInt),
+}
+
+impl Value {
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::Signed(v) => Some(*v),
+            Value::Unsigned(v) => i128::try_from(*v).ok(),
+            Value::Big(v) => v.to_i128(),
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Signed(v) => u128::try_from(*v).ok(),
+            Value::Unsigned(v) => Some(*v),
+            Value::Big(v) => v.to_u128(),
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Value::Signed(v) => *v < 0,
+            Value::Unsigned(_) => false,
+            Value::Big(v) => v.is_negative(),
+        }
+    }
+
+    pub fn to_big(&self) -> BigInt {
+        match self {
+            Value::Signed(v) => BigInt::from(*v),
+            Value::Unsigned(v) => BigInt::from(*v),
+            Value::Big(v) => v.clone(),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_big() == other.to_big()
+    }
+}
+
+impl Eq for Value {}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Signed(v) => write!(f, "{v}"),
+            Value::Unsigned(v) => write!(f, "{v}"),
+            Value::Big(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        Value::Big(value)
+    }
+}
+
+macro_rules! impl_from_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(value: $ty) -> Self {
+                    Value::Signed(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(value: $ty) -> Self {
+                    Value::Unsigned(u128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed!(i8, i16, i32, i64, i128);
+impl_from_unsigned!(u8, u16, u32, u64, u128);
@@ -1,4 +1,7 @@
-use calc_core::{eval_expression, CalcError};
+mod common;
+
+use calc_core::{eval_expression, BinaryOp, CalcError, ErrorKind};
+use common::overflow;
 
 // 受け入れテスト
 #[test]
@@ -16,6 +19,11 @@ fn acceptance_mixed_bases() {
     assert_eq!(eval_expression("0x10 + 0b11"), Ok(19));
 }
 
+#[test]
+fn acceptance_octal_permissions() {
+    assert_eq!(eval_expression("0o777 & ~0o022"), Ok(0o755));
+}
+
 #[test]
 fn acceptance_negative_remainder() {
     assert_eq!(eval_expression("-5 % 3"), Ok(1));
@@ -24,23 +32,23 @@ fn acceptance_negative_remainder() {
 #[test]
 fn acceptance_addition_range_error() {
     assert_eq!(
-        eval_expression("2147483647 + 1"),
-        Err(CalcError::RangeError)
+        eval_expression("2147483647 + 1").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[2147483647, 1]))
     );
 }
 
 #[test]
 fn acceptance_min_subtraction_range_error() {
     assert_eq!(
-        eval_expression("-2147483648 - 1"),
-        Err(CalcError::RangeError)
+        eval_expression("-2147483648 - 1").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Sub, &[-2147483648, 1]))
     );
 }
 
 #[test]
 fn acceptance_literal_range_error() {
     assert_eq!(
-        eval_expression("0x1_0000_0000"),
-        Err(CalcError::LiteralOutOfRange)
+        eval_expression("0x1_0000_0000").map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
     );
 }
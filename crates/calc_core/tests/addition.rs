@@ -1,4 +1,7 @@
-use calc_core::{eval_expression, CalcError};
+mod common;
+
+use calc_core::{eval_expression, BinaryOp, CalcError, ErrorKind};
+use common::overflow;
 
 #[test]
 fn addition_single() {
@@ -13,15 +16,15 @@ fn addition_multiple_with_spaces() {
 #[test]
 fn addition_literal_range_error() {
     assert_eq!(
-        eval_expression("2147483648"),
-        Err(CalcError::LiteralOutOfRange)
+        eval_expression("2147483648").map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
     );
 }
 
 #[test]
 fn addition_result_range_error() {
     assert_eq!(
-        eval_expression("2147483647 + 1"),
-        Err(CalcError::RangeError)
+        eval_expression("2147483647 + 1").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[2147483647, 1]))
     );
 }
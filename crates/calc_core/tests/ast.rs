@@ -0,0 +1,166 @@
+use calc_core::{
+    parse_ast, BigInt, BinaryOp, CalcError, Context, ErrorKind, EvalOptions, Expr, Literal,
+    LiteralBase, Span, UnaryOp, Value, Width,
+};
+
+fn literal(base: LiteralBase, text: &str, value: u32, start: usize) -> Expr {
+    Expr::Literal(Literal {
+        base,
+        text: text.to_string(),
+        value: BigInt::from(value),
+        span: Span::new(start, start + text.len()),
+    })
+}
+
+fn binary(op: BinaryOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        span: Span::new(lhs.span().start, rhs.span().end),
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+// 構文木の形
+#[test]
+fn ast_keeps_literal_base() {
+    assert_eq!(
+        parse_ast("0xFF << 0b1000"),
+        Ok(binary(
+            BinaryOp::Shl,
+            literal(LiteralBase::Hex, "0xFF", 255, 0),
+            literal(LiteralBase::Binary, "0b1000", 8, 8),
+        ))
+    );
+    assert_eq!(
+        parse_ast("'A'"),
+        Ok(literal(LiteralBase::Char, "'A'", 65, 0))
+    );
+}
+
+#[test]
+fn ast_precedence_and_associativity() {
+    assert_eq!(
+        parse_ast("1 + 2 * 3 - 4"),
+        Ok(binary(
+            BinaryOp::Sub,
+            binary(
+                BinaryOp::Add,
+                literal(LiteralBase::Decimal, "1", 1, 0),
+                binary(
+                    BinaryOp::Mul,
+                    literal(LiteralBase::Decimal, "2", 2, 4),
+                    literal(LiteralBase::Decimal, "3", 3, 8),
+                ),
+            ),
+            literal(LiteralBase::Decimal, "4", 4, 12),
+        ))
+    );
+    assert_eq!(
+        parse_ast("2 ** 3 ** 2"),
+        Ok(binary(
+            BinaryOp::Pow,
+            literal(LiteralBase::Decimal, "2", 2, 0),
+            binary(
+                BinaryOp::Pow,
+                literal(LiteralBase::Decimal, "3", 3, 5),
+                literal(LiteralBase::Decimal, "2", 2, 10),
+            ),
+        ))
+    );
+}
+
+#[test]
+fn ast_unary_and_parens() {
+    assert_eq!(
+        parse_ast("-(~x)"),
+        Ok(Expr::Unary {
+            op: UnaryOp::Neg,
+            operand: Box::new(Expr::Paren {
+                inner: Box::new(Expr::Unary {
+                    op: UnaryOp::Not,
+                    operand: Box::new(Expr::Variable {
+                        name: "x".to_string(),
+                        span: Span::new(3, 4),
+                    }),
+                    span: Span::new(2, 4),
+                }),
+                span: Span::new(1, 5),
+            }),
+            span: Span::new(0, 5),
+        })
+    );
+}
+
+#[test]
+fn ast_calls() {
+    assert_eq!(
+        parse_ast("max(1, y)"),
+        Ok(Expr::Call {
+            name: "max".to_string(),
+            args: vec![
+                literal(LiteralBase::Decimal, "1", 1, 4),
+                Expr::Variable {
+                    name: "y".to_string(),
+                    span: Span::new(7, 8),
+                },
+            ],
+            span: Span::new(0, 9),
+        })
+    );
+}
+
+// 構文エラーは解析時に検出する
+#[test]
+fn ast_parse_errors() {
+    assert_eq!(
+        parse_ast("1 +").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('+'))
+    );
+    assert_eq!(
+        parse_ast("(1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
+    assert_eq!(
+        parse_ast("0x1G").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse_ast("foo(1)").map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownFunction("foo".to_string()))
+    );
+    assert_eq!(
+        parse_ast("x = 1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('='))
+    );
+}
+
+// 範囲エラーは評価時に検出する
+#[test]
+fn ast_eval_with_options() {
+    let expr = parse_ast("0xFF + 1").unwrap();
+    let w8 = EvalOptions {
+        width: Width::W8,
+        ..EvalOptions::default()
+    };
+    assert_eq!(expr.eval(&EvalOptions::default()), Ok(Value::from(256)));
+    assert_eq!(
+        expr.eval(&w8).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(parse_ast("-128").unwrap().eval(&w8), Ok(Value::from(-128)));
+}
+
+#[test]
+fn ast_eval_reuses_context() {
+    let expr = parse_ast("x * 2 + 1").unwrap();
+    let mut context = Context::new();
+    assert_eq!(
+        expr.eval_in(&context).map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownIdentifier("x".to_string()))
+    );
+    context.set("x", 10);
+    assert_eq!(expr.eval_in(&context), Ok(Value::from(21)));
+    context.set("x", -3);
+    assert_eq!(expr.eval_in(&context), Ok(Value::from(-5)));
+}
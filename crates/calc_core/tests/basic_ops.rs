@@ -1,4 +1,4 @@
-use calc_core::eval_expression;
+use calc_core::{eval_expression, Value};
 
 // 加算
 #[test]
@@ -39,8 +39,8 @@ fn subtraction_negative_result_ok() {
 #[test]
 fn remainder_divide_by_zero_error() {
     assert_eq!(
-        eval_expression("5%0"),
-        Err(calc_core::CalcError::RangeError)
+        eval_expression("5%0").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::RemainderByZero)
     );
 }
 
@@ -48,8 +48,8 @@ fn remainder_divide_by_zero_error() {
 #[test]
 fn trailing_plus_operator_error() {
     assert_eq!(
-        eval_expression("1+"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("1+").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
@@ -57,8 +57,8 @@ fn trailing_plus_operator_error() {
 #[test]
 fn trailing_minus_operator_error() {
     assert_eq!(
-        eval_expression("1-"),
-        Err(calc_core::CalcError::InvalidToken('-'))
+        eval_expression("1-").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('-'))
     );
 }
 
@@ -66,8 +66,8 @@ fn trailing_minus_operator_error() {
 #[test]
 fn trailing_multiply_operator_error() {
     assert_eq!(
-        eval_expression("1*"),
-        Err(calc_core::CalcError::InvalidToken('*'))
+        eval_expression("1*").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('*'))
     );
 }
 
@@ -75,8 +75,8 @@ fn trailing_multiply_operator_error() {
 #[test]
 fn trailing_remainder_operator_error() {
     assert_eq!(
-        eval_expression("1%"),
-        Err(calc_core::CalcError::InvalidToken('%'))
+        eval_expression("1%").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('%'))
     );
 }
 
@@ -84,56 +84,56 @@ fn trailing_remainder_operator_error() {
 #[test]
 fn consecutive_plus_operator_error() {
     assert_eq!(
-        eval_expression("1++2"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("1++2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
 #[test]
 fn consecutive_multiply_operator_error() {
     assert_eq!(
-        eval_expression("1**2"),
-        Err(calc_core::CalcError::InvalidToken('*'))
+        eval_expression("1***2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('*'))
     );
 }
 
 #[test]
 fn consecutive_remainder_operator_error() {
     assert_eq!(
-        eval_expression("1%%2"),
-        Err(calc_core::CalcError::InvalidToken('%'))
+        eval_expression("1%%2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('%'))
     );
 }
 
 #[test]
 fn consecutive_mixed_plus_multiply_operator_error() {
     assert_eq!(
-        eval_expression("1+*2"),
-        Err(calc_core::CalcError::InvalidToken('*'))
+        eval_expression("1+*2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('*'))
     );
 }
 
 #[test]
 fn consecutive_mixed_multiply_plus_operator_error() {
     assert_eq!(
-        eval_expression("1*+2"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("1*+2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
 #[test]
 fn consecutive_mixed_minus_plus_operator_error() {
     assert_eq!(
-        eval_expression("1-+2"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("1-+2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
 #[test]
 fn consecutive_mixed_remainder_plus_operator_error() {
     assert_eq!(
-        eval_expression("1%+2"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("1%+2").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
@@ -141,24 +141,24 @@ fn consecutive_mixed_remainder_plus_operator_error() {
 #[test]
 fn leading_plus_operator_error() {
     assert_eq!(
-        eval_expression("+1"),
-        Err(calc_core::CalcError::InvalidToken('+'))
+        eval_expression("+1").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('+'))
     );
 }
 
 #[test]
 fn leading_multiply_operator_error() {
     assert_eq!(
-        eval_expression("*1"),
-        Err(calc_core::CalcError::InvalidToken('*'))
+        eval_expression("*1").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('*'))
     );
 }
 
 #[test]
 fn leading_remainder_operator_error() {
     assert_eq!(
-        eval_expression("%1"),
-        Err(calc_core::CalcError::InvalidToken('%'))
+        eval_expression("%1").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('%'))
     );
 }
 
@@ -166,8 +166,8 @@ fn leading_remainder_operator_error() {
 #[test]
 fn minus_only_operator_error() {
     assert_eq!(
-        eval_expression("-"),
-        Err(calc_core::CalcError::InvalidToken('-'))
+        eval_expression("-").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::InvalidToken('-'))
     );
 }
 
@@ -201,7 +201,10 @@ fn min_i32_literal_ok() {
 #[test]
 fn min_i32_subtraction_range_error() {
     assert_eq!(
-        eval_expression("-2147483648-1"),
-        Err(calc_core::CalcError::RangeError)
+        eval_expression("-2147483648-1").map_err(calc_core::CalcError::into_kind),
+        Err(calc_core::ErrorKind::Overflow {
+            op: calc_core::Operation::Binary(calc_core::BinaryOp::Sub),
+            operands: vec![Value::from(-2147483648), Value::from(1)],
+        })
     );
 }
@@ -0,0 +1,159 @@
+mod common;
+
+use calc_core::{
+    eval_expression, eval_in, eval_with, format_all_with, BigInt, BinaryOp, CalcError, Context,
+    ErrorKind, EvalOptions, Precision, Value,
+};
+use common::overflow;
+
+fn big() -> EvalOptions {
+    EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    }
+}
+
+fn eval_big(input: &str) -> Result<String, CalcError> {
+    eval_with(input, &big()).map(|value| value.to_string())
+}
+
+// 既定は固定幅
+#[test]
+fn bigint_default_is_fixed() {
+    assert_eq!(EvalOptions::default().precision, Precision::Fixed);
+    assert_eq!(
+        eval_expression("2**40").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[2, 40]))
+    );
+}
+
+// 四則演算
+#[test]
+fn bigint_arithmetic() {
+    assert_eq!(
+        eval_big("2**200"),
+        Ok("1606938044258990275541962092341162602522202993782792835301376".to_string())
+    );
+    assert_eq!(
+        eval_big("(2**127 - 1) * (2**89 - 1)"),
+        Ok("105312291668557186697918027513529248857806893649219117400977309697".to_string())
+    );
+    assert_eq!(eval_big("2**200 - 2**200"), Ok("0".to_string()));
+    assert_eq!(eval_big("-(2**100) + 2**100 + 1"), Ok("1".to_string()));
+}
+
+#[test]
+fn bigint_division_is_euclidean() {
+    assert_eq!(
+        eval_big("(2**200 + 12345) / (2**100 + 7)"),
+        Ok("1267650600228229401496703205369".to_string())
+    );
+    assert_eq!(
+        eval_big("(2**200 + 12345) % (2**100 + 7)"),
+        Ok("12394".to_string())
+    );
+    assert_eq!(
+        eval_big("-(2**130) / 3"),
+        Ok("-453709822561251284617832809909024281942".to_string())
+    );
+    assert_eq!(eval_big("-(2**130) % 3"), Ok("2".to_string()));
+    assert_eq!(
+        eval_big("2**200 / 0").map_err(CalcError::into_kind),
+        Err(ErrorKind::DivisionByZero)
+    );
+}
+
+// リテラル
+#[test]
+fn bigint_literals() {
+    assert_eq!(
+        eval_big("0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF + 1"),
+        eval_big("2**144")
+    );
+    assert_eq!(
+        eval_big("-123456789012345678901234567890123456789012345678901234567890"),
+        Ok("-123456789012345678901234567890123456789012345678901234567890".to_string())
+    );
+    assert_eq!(eval_big("36rZZ"), Ok("1295".to_string()));
+    assert_eq!(
+        eval_big("b\"0123456789ABCDEFGHIJ\""),
+        eval_big("0x3031_3233_3435_3637_3839_4142_4344_4546_4748_494A")
+    );
+}
+
+// ビット演算
+#[test]
+fn bigint_bitwise() {
+    assert_eq!(eval_big("(2**130 | 1) & ~1"), eval_big("2**130"));
+    assert_eq!(eval_big("~0"), Ok("-1".to_string()));
+    assert_eq!(
+        eval_big("-1 ^ 2**100"),
+        Ok("-1267650600228229401496703205377".to_string())
+    );
+    assert_eq!(eval_big("1 << 1000"), eval_big("2**1000"));
+    assert_eq!(eval_big("-(2**130) >> 129"), Ok("-2".to_string()));
+    assert_eq!(eval_big("-(2**130 + 1) >> 129"), Ok("-3".to_string()));
+}
+
+#[test]
+fn bigint_width_dependent_operations_are_rejected() {
+    assert_eq!(
+        eval_big("-1 >>> 4").map_err(CalcError::into_kind),
+        Err(ErrorKind::RequiresFixedWidth(">>>".to_string()))
+    );
+    assert_eq!(
+        eval_big("1 rotl 3").map_err(CalcError::into_kind),
+        Err(ErrorKind::RequiresFixedWidth("rotl".to_string()))
+    );
+    assert_eq!(
+        eval_big("popcount(7)").map_err(CalcError::into_kind),
+        Err(ErrorKind::RequiresFixedWidth("popcount".to_string()))
+    );
+    assert_eq!(eval_big("abs(-(2**200))"), eval_big("2**200"));
+    assert_eq!(eval_big("max(2**200, 2**201)"), eval_big("2**201"));
+}
+
+// 上限
+#[test]
+fn bigint_size_limit() {
+    assert!(eval_big("2**65535").is_ok());
+    assert_eq!(
+        eval_big("2**65536").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[2, 65536]))
+    );
+    assert_eq!(
+        eval_big("1 << 70000").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+}
+
+// 出力
+#[test]
+fn bigint_format_all() {
+    let value = eval_with("2**128 + 0xABC", &big()).unwrap();
+    let formatted = format_all_with(value, &big()).unwrap();
+    assert_eq!(formatted.hex, "0x100000000000000000000000000000ABC");
+    assert_eq!(formatted.dec, "340282366920938463463374607431768214204");
+    assert_eq!(
+        formatted.oct,
+        "0o4000000000000000000000000000000000000005274"
+    );
+
+    let negative = eval_with("-(2**70)", &big()).unwrap();
+    let formatted = format_all_with(negative, &big()).unwrap();
+    assert_eq!(formatted.oct, "-0o200000000000000000000000");
+    assert_eq!(formatted.oct_twos, formatted.oct);
+}
+
+#[test]
+fn bigint_context_and_value() {
+    let mut ctx = Context::with_options(big());
+    eval_in(&mut ctx, "p = 2**127 - 1").unwrap();
+    assert_eq!(
+        eval_in(&mut ctx, "p * p % 1000"),
+        Ok(Value::from(BigInt::from(529u32)))
+    );
+    assert_eq!(ctx.get("p"), Some(Value::from(i128::MAX)));
+    assert_eq!(Value::from(BigInt::from(5u32)), Value::from(5));
+    assert_eq!(BigInt::from(-255i128).to_str_radix(16, true), "-FF");
+}
@@ -0,0 +1,104 @@
+use calc_core::{eval_expression, CalcError, ErrorKind};
+
+// ビット演算
+#[test]
+fn bitwise_and() {
+    assert_eq!(eval_expression("0b1100 & 0b1010"), Ok(0b1000));
+}
+
+#[test]
+fn bitwise_or() {
+    assert_eq!(eval_expression("0b1100 | 0b1010"), Ok(0b1110));
+}
+
+#[test]
+fn bitwise_xor() {
+    assert_eq!(eval_expression("0b1100 ^ 0b1010"), Ok(0b0110));
+}
+
+#[test]
+fn bitwise_not() {
+    assert_eq!(eval_expression("~0"), Ok(-1));
+    assert_eq!(eval_expression("~0x7FFF_FFFF"), Ok(-2147483648));
+}
+
+#[test]
+fn bitwise_not_mask() {
+    assert_eq!(eval_expression("0xFF_FF & ~0xF0"), Ok(0xFF0F));
+}
+
+#[test]
+fn bitwise_negative_operands() {
+    assert_eq!(eval_expression("-1 & 0xFF"), Ok(0xFF));
+    assert_eq!(eval_expression("-256 | 0xFF"), Ok(-1));
+}
+
+// 優先順位（C と同じ: * > + > & > ^ > |）
+#[test]
+fn bitwise_precedence_and_over_xor_over_or() {
+    assert_eq!(eval_expression("1 | 6 ^ 3 & 5"), Ok(7));
+}
+
+#[test]
+fn bitwise_precedence_below_additive() {
+    assert_eq!(eval_expression("1 + 2 & 3"), Ok(3));
+    assert_eq!(eval_expression("4 | 1 * 2"), Ok(6));
+}
+
+#[test]
+fn bitwise_not_binds_like_unary_minus() {
+    assert_eq!(eval_expression("~1 + 1"), Ok(-1));
+    assert_eq!(eval_expression("-~1"), Ok(2));
+    assert_eq!(eval_expression("~-1"), Ok(0));
+}
+
+#[test]
+fn bitwise_with_parentheses() {
+    assert_eq!(eval_expression("(0xF0 | 0x0F) & ~(1 + 1)"), Ok(0xFD));
+}
+
+// 範囲
+#[test]
+fn bitwise_not_literal_range_error() {
+    assert_eq!(
+        eval_expression("~2147483648").map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+// 演算子の異常系
+#[test]
+fn trailing_bitwise_operator_error() {
+    assert_eq!(
+        eval_expression("1&").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('&'))
+    );
+    assert_eq!(
+        eval_expression("1|").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('|'))
+    );
+    assert_eq!(
+        eval_expression("1^").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('^'))
+    );
+    assert_eq!(
+        eval_expression("~").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('~'))
+    );
+}
+
+#[test]
+fn consecutive_bitwise_operator_error() {
+    assert_eq!(
+        eval_expression("1&&2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('&'))
+    );
+    assert_eq!(
+        eval_expression("1|&2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('&'))
+    );
+    assert_eq!(
+        eval_expression("1~2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('~'))
+    );
+}
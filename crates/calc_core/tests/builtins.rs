@@ -0,0 +1,135 @@
+use calc_core::{eval_expression, eval_in, CalcError, Context, ErrorKind, Operation, Value};
+
+// ビット操作関数
+#[test]
+fn builtin_popcount() {
+    assert_eq!(eval_expression("popcount(0b1011_0110)"), Ok(5));
+    assert_eq!(eval_expression("popcount(-1)"), Ok(32));
+    assert_eq!(eval_expression("popcount(0)"), Ok(0));
+}
+
+#[test]
+fn builtin_clz_ctz() {
+    assert_eq!(eval_expression("clz(1)"), Ok(31));
+    assert_eq!(eval_expression("clz(0)"), Ok(32));
+    assert_eq!(eval_expression("clz(-1)"), Ok(0));
+    assert_eq!(eval_expression("ctz(0x100)"), Ok(8));
+    assert_eq!(eval_expression("ctz(0)"), Ok(32));
+}
+
+#[test]
+fn builtin_bswap() {
+    assert_eq!(eval_expression("bswap(0x1234_5678)"), Ok(0x7856_3412));
+    assert_eq!(eval_expression("bswap(0xFF)"), Ok(-16777216));
+}
+
+#[test]
+fn builtin_bitrev() {
+    assert_eq!(eval_expression("bitrev(1)"), Ok(-2147483648));
+    assert_eq!(eval_expression("bitrev(0xF0)"), Ok(0x0F00_0000));
+    assert_eq!(eval_expression("bitrev(bitrev(0x1234))"), Ok(0x1234));
+}
+
+// 算術関数
+#[test]
+fn builtin_abs() {
+    assert_eq!(eval_expression("abs(-5)"), Ok(5));
+    assert_eq!(eval_expression("abs(5)"), Ok(5));
+}
+
+#[test]
+fn builtin_abs_min_value_range_error() {
+    assert_eq!(
+        eval_expression("abs(-2147483648)").map_err(CalcError::into_kind),
+        Err(ErrorKind::Overflow {
+            op: Operation::Call("abs".to_string()),
+            operands: vec![Value::from(-2147483648)],
+        })
+    );
+}
+
+#[test]
+fn builtin_min_max() {
+    assert_eq!(eval_expression("min(3, -7)"), Ok(-7));
+    assert_eq!(eval_expression("max(3, -7)"), Ok(3));
+    assert_eq!(eval_expression("max(min(10, 20), 15)"), Ok(15));
+}
+
+// 式の中での関数呼び出し
+#[test]
+fn builtin_in_expression() {
+    assert_eq!(eval_expression("1 << ctz(0x40) | 1"), Ok(0x41));
+    assert_eq!(eval_expression("-popcount(7)**2"), Ok(-9));
+    assert_eq!(eval_expression("max ( 1 + 2 , 2 * 2 )"), Ok(4));
+}
+
+#[test]
+fn builtin_with_variables() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "mask = 0xF0F0").unwrap();
+    assert_eq!(eval_in(&mut ctx, "popcount(mask)"), Ok(Value::from(8)));
+}
+
+#[test]
+fn builtin_name_shadows_nothing_without_call() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "abs = 3").unwrap();
+    assert_eq!(eval_in(&mut ctx, "abs(-abs)"), Ok(Value::from(3)));
+}
+
+// 異常系
+#[test]
+fn builtin_unknown_function_error() {
+    assert_eq!(
+        eval_expression("sqrt(4)").map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownFunction("sqrt".to_string()))
+    );
+}
+
+#[test]
+fn builtin_arity_error() {
+    assert_eq!(
+        eval_expression("min(1)").map_err(CalcError::into_kind),
+        Err(ErrorKind::ArityMismatch {
+            name: "min".to_string(),
+            expected: 2,
+            found: 1,
+        })
+    );
+    assert_eq!(
+        eval_expression("popcount()").map_err(CalcError::into_kind),
+        Err(ErrorKind::ArityMismatch {
+            name: "popcount".to_string(),
+            expected: 1,
+            found: 0,
+        })
+    );
+    assert_eq!(
+        eval_expression("abs(1, 2)").map_err(CalcError::into_kind),
+        Err(ErrorKind::ArityMismatch {
+            name: "abs".to_string(),
+            expected: 1,
+            found: 2,
+        })
+    );
+}
+
+#[test]
+fn builtin_call_syntax_error() {
+    assert_eq!(
+        eval_expression("min(1, 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
+    assert_eq!(
+        eval_expression("min(1,").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(','))
+    );
+    assert_eq!(
+        eval_expression("min(1,)").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
+    assert_eq!(
+        eval_expression("min(1 2)").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
+}
@@ -0,0 +1,116 @@
+use calc_core::{eval_expression, parse, CalcError, ErrorKind};
+
+// 文字リテラル
+#[test]
+fn char_literal_ascii() {
+    assert_eq!(parse("'A'"), Ok(0x41));
+    assert_eq!(parse("' '"), Ok(0x20));
+}
+
+#[test]
+fn char_literal_escapes() {
+    assert_eq!(parse(r"'\n'"), Ok(0x0A));
+    assert_eq!(parse(r"'\r'"), Ok(0x0D));
+    assert_eq!(parse(r"'\t'"), Ok(0x09));
+    assert_eq!(parse(r"'\0'"), Ok(0x00));
+    assert_eq!(parse(r"'\\'"), Ok(0x5C));
+    assert_eq!(parse(r"'\''"), Ok(0x27));
+    assert_eq!(parse(r#"'\"'"#), Ok(0x22));
+    assert_eq!(parse(r"'\x7f'"), Ok(0x7F));
+}
+
+#[test]
+fn char_literal_unicode_scalar() {
+    assert_eq!(parse("'é'"), Ok(0xE9));
+    assert_eq!(parse("'あ'"), Ok(0x3042));
+}
+
+#[test]
+fn char_literal_in_expression() {
+    assert_eq!(eval_expression("'a' - 'A'"), Ok(32));
+    assert_eq!(eval_expression("-'0'"), Ok(-48));
+}
+
+#[test]
+fn char_literal_invalid() {
+    assert_eq!(
+        parse("''").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("'AB'").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("'A").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse(r"'\q'").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse(r"'\x4'").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+}
+
+// バイト列リテラル（既定はビッグエンディアン）
+#[test]
+fn byte_string_big_endian() {
+    assert_eq!(parse(r#"b"RIFF""#), Ok(0x5249_4646));
+    assert_eq!(parse(r#"b"RIFF"be"#), Ok(0x5249_4646));
+}
+
+#[test]
+fn byte_string_little_endian() {
+    assert_eq!(parse(r#"b"RIFF"le"#), Ok(0x4646_4952));
+    assert_eq!(parse(r#"b"MZ"le"#), Ok(0x5A4D));
+}
+
+#[test]
+fn byte_string_escapes() {
+    assert_eq!(parse(r#"b"\x7fELF""#), Ok(0x7F45_4C46));
+    assert_eq!(parse(r#"b"\0\n""#), Ok(0x000A));
+}
+
+#[test]
+fn byte_string_in_expression() {
+    assert_eq!(eval_expression(r#"b"AB" + 1"#), Ok(0x4143));
+}
+
+#[test]
+fn byte_string_out_of_range_error() {
+    assert_eq!(
+        parse(r#"b"\xff\x00\x00\x00""#).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        parse(r#"b"\x00\x00\x00\xff"le"#).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        parse(r#"b"ABCDE""#).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+#[test]
+fn byte_string_invalid() {
+    assert_eq!(
+        parse(r#"b"""#).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse(r#"b"AB"#).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse(r#"b"é""#).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse(r#"b"AB"xx"#).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+}
@@ -0,0 +1,206 @@
+use calc_core::{
+    codegen, CalcError, ErrorKind, EvalOptions, GeneratedCode, Language, OverflowPolicy, Precision,
+    Signedness, Span, Width,
+};
+
+fn options(width: Width, signedness: Signedness, overflow: OverflowPolicy) -> EvalOptions {
+    EvalOptions {
+        width,
+        signedness,
+        overflow,
+        ..EvalOptions::default()
+    }
+}
+
+fn expr(input: &str, language: Language, options: &EvalOptions) -> String {
+    codegen(input, language, options).unwrap().expr
+}
+
+// Rust: 標準のメソッドで同じオーバーフローの扱いにする
+#[test]
+fn rust_uses_policy_methods() {
+    let checked = EvalOptions::default();
+    assert_eq!(
+        codegen("0xFF + x * 2", Language::Rust, &checked).unwrap(),
+        GeneratedCode {
+            helpers: String::new(),
+            expr: "0xFF_i32.checked_add(x.checked_mul(2_i32).unwrap()).unwrap()".to_string(),
+        }
+    );
+    let wrapping = options(Width::W8, Signedness::Signed, OverflowPolicy::Wrapping);
+    assert_eq!(
+        expr("x - y ** 3", Language::Rust, &wrapping),
+        "x.wrapping_sub(calc_pow(y, 3_i8))"
+    );
+    let saturating = options(Width::W16, Signedness::Unsigned, OverflowPolicy::Saturating);
+    assert_eq!(
+        expr("-x + popcount(y)", Language::Rust, &saturating),
+        "0_u16.saturating_sub(x).saturating_add(y.count_ones() as u16)"
+    );
+}
+
+// 除算と余りは Euclidean
+#[test]
+fn euclidean_division() {
+    let signed = EvalOptions::default();
+    assert_eq!(
+        expr("x / y % 7", Language::Rust, &signed),
+        "x.checked_div_euclid(y).unwrap().wrapping_rem_euclid(7_i32)"
+    );
+    let unsigned = options(Width::W32, Signedness::Unsigned, OverflowPolicy::Checked);
+    assert_eq!(
+        expr("x / y % 7", Language::Rust, &unsigned),
+        "(x / y) % 7_u32"
+    );
+
+    let python = codegen("x % y", Language::Python, &signed).unwrap();
+    assert_eq!(python.expr, "_rem(_var(x), _var(y))");
+    assert!(python.helpers.contains("return a % abs(b)"));
+
+    let c = codegen("x / y", Language::C, &signed).unwrap();
+    assert!(c
+        .helpers
+        .contains("if (a % b < 0)\n        q = b > 0 ? q - 1 : q + 1;"));
+}
+
+// リテラルは元の基数のまま、折り返した値はその値を書く
+#[test]
+fn literals_keep_base() {
+    let checked = EvalOptions::default();
+    assert_eq!(
+        expr(
+            "0b1010 | 0o17 ^ 0x7ead_BEEF & 1_000",
+            Language::Python,
+            &checked
+        ),
+        "0b1010 | (0o17 ^ (0x7ead_BEEF & 1_000))"
+    );
+    // 2・8・16 進以外の基数はどの言語でも書けないので 10 進になる
+    assert_eq!(expr("36rZZ", Language::Python, &checked), "1295");
+    assert_eq!(
+        expr("3r120 + 7r66", Language::Rust, &checked),
+        "15_i32.checked_add(48_i32).unwrap()"
+    );
+    assert_eq!(
+        expr("16rff + 2r1010", Language::Python, &checked),
+        "_fit(0xFF + 0b1010)"
+    );
+    assert_eq!(
+        expr("8r17 | 16r10", Language::C, &checked),
+        "(int32_t)(017 | 0x10)"
+    );
+    assert_eq!(expr("2r1010", Language::Verilog, &checked), "32'sb1010");
+    assert_eq!(expr("'A'", Language::C, &checked), "'A'");
+    assert_eq!(expr("'A'", Language::Rust, &checked), "b'A' as i32");
+    assert_eq!(expr("0o17", Language::C, &checked), "017");
+
+    let wrapping = options(Width::W8, Signedness::Signed, OverflowPolicy::Wrapping);
+    assert_eq!(expr("0xFF", Language::Rust, &wrapping), "-0x1_i8");
+    assert_eq!(expr("-0x80", Language::Verilog, &wrapping), "-8'sh80");
+
+    let unsigned = options(Width::W16, Signedness::Unsigned, OverflowPolicy::Checked);
+    assert_eq!(expr("0xFF00", Language::Verilog, &unsigned), "16'hFF00");
+}
+
+// C: 補助関数は必要なものだけ出力する
+#[test]
+fn c_helpers() {
+    let code = codegen("x + 0b1010 & 0x0F", Language::C, &EvalOptions::default()).unwrap();
+    assert_eq!(code.expr, "(int32_t)(calc_add(x, 0b1010) & 0x0F)");
+    assert_eq!(
+        code.helpers,
+        "#include <stdint.h>\n\
+         #include <stdlib.h>\n\
+         \n\
+         static inline int32_t calc_add(int32_t a, int32_t b)\n\
+         {\n    \
+             int32_t r;\n    \
+             if (__builtin_add_overflow(a, b, &r))\n        \
+                 abort();\n    \
+             return r;\n\
+         }\n"
+    );
+
+    let saturating = options(Width::W64, Signedness::Signed, OverflowPolicy::Saturating);
+    let code = codegen("-x * y", Language::C, &saturating).unwrap();
+    assert_eq!(code.expr, "calc_mul(calc_neg(x), y)");
+    assert!(code
+        .helpers
+        .contains("return (a < 0) != (b < 0) ? INT64_MIN : INT64_MAX;"));
+
+    let wide = options(Width::W128, Signedness::Unsigned, OverflowPolicy::Checked);
+    assert_eq!(
+        expr("0xFFFF_FFFF_FFFF_FFFF_FFFF", Language::C, &wide),
+        "(unsigned __int128)(((unsigned __int128)0xFFFF << 64) | 0xFFFFFFFFFFFFFFFF)"
+    );
+}
+
+// Verilog: 範囲外やゼロ除算は x になる
+#[test]
+fn verilog_functions() {
+    let code = codegen("x / 3", Language::Verilog, &EvalOptions::default()).unwrap();
+    assert_eq!(code.expr, "calc_div(x, 32'sd3)");
+    assert!(code.helpers.starts_with(
+        "function automatic signed [31:0] calc_div(input signed [31:0] a, input signed [31:0] b);\n"
+    ));
+    assert!(code.helpers.contains("calc_div = {32{1'bx}};"));
+    assert_eq!(
+        expr("x & ~y", Language::Verilog, &EvalOptions::default()),
+        "x & (~y)"
+    );
+}
+
+// Python は多倍長モードも扱える
+#[test]
+fn python_arbitrary_precision() {
+    let arbitrary = EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    };
+    let code = codegen("x << 100", Language::Python, &arbitrary).unwrap();
+    assert_eq!(code.expr, "_shl(_var(x), 100)");
+    assert!(code.helpers.starts_with("_MAX_BITS = 65536\n"));
+    assert!(code
+        .helpers
+        .contains("return value.bit_length() <= _MAX_BITS"));
+}
+
+#[test]
+fn errors() {
+    let arbitrary = EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    };
+    assert_eq!(
+        codegen("1 + 2", Language::C, &arbitrary),
+        Err(CalcError::new(
+            ErrorKind::RequiresFixedWidth("C".to_string()),
+            Span::new(0, 5)
+        ))
+    );
+    assert_eq!(
+        codegen("x >>> 1", Language::Python, &arbitrary),
+        Err(CalcError::new(
+            ErrorKind::RequiresFixedWidth(">>>".to_string()),
+            Span::new(0, 7)
+        ))
+    );
+    assert_eq!(
+        codegen("x + 0x1_0000_0000", Language::Rust, &EvalOptions::default()),
+        Err(CalcError::new(
+            ErrorKind::LiteralOutOfRange,
+            Span::new(4, 17)
+        ))
+    );
+    assert_eq!(
+        codegen("max(x)", Language::Rust, &EvalOptions::default()),
+        Err(CalcError::new(
+            ErrorKind::ArityMismatch {
+                name: "max".to_string(),
+                expected: 2,
+                found: 1
+            },
+            Span::new(0, 6)
+        ))
+    );
+}
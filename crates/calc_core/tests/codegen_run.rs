@@ -0,0 +1,456 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use calc_core::{
+    codegen, eval_in, Context, EvalOptions, GeneratedCode, Language, OverflowPolicy, Signedness,
+    Value, Width,
+};
+
+// 生成したコードを実際にビルド・実行して calc_core の評価結果と比べる
+
+const INPUTS: &[&str] = &[
+    "x + y",
+    "x - y",
+    "x * y",
+    "x / y",
+    "x % y",
+    "-x",
+    "-x / -y % 5",
+    "~x & y | x ^ 3",
+    "x ** 3",
+    "y ** 2 ** 2",
+    "x ** y",
+    "(-1) ** y",
+    "x << 3",
+    "x << y",
+    "x >> 2",
+    "x >>> 1",
+    "x rotl 3",
+    "y rotr x",
+    "popcount(x) + clz(y)",
+    "ctz(x) * 2",
+    "bswap(x) ^ bitrev(y)",
+    "abs(x) - 1",
+    "min(x, y) - max(x, 1)",
+    "0x7F * 2 + x",
+    "-0x80 + x",
+    "'A' + 0b1010 * x",
+];
+
+const TYPES: &[(Width, Signedness, &str, &str)] = &[
+    (Width::W8, Signedness::Signed, "i8", "int8_t"),
+    (Width::W16, Signedness::Unsigned, "u16", "uint16_t"),
+    (Width::W32, Signedness::Signed, "i32", "int32_t"),
+    (Width::W64, Signedness::Signed, "i64", "int64_t"),
+    (Width::W64, Signedness::Unsigned, "u64", "uint64_t"),
+    (Width::W128, Signedness::Signed, "i128", "__int128"),
+    (
+        Width::W128,
+        Signedness::Unsigned,
+        "u128",
+        "unsigned __int128",
+    ),
+];
+
+const POLICIES: &[OverflowPolicy] = &[
+    OverflowPolicy::Checked,
+    OverflowPolicy::Wrapping,
+    OverflowPolicy::Saturating,
+];
+
+struct Case {
+    code: GeneratedCode,
+    width: Width,
+    signedness: Signedness,
+    rust_type: &'static str,
+    c_type: &'static str,
+    pairs: Vec<(Value, Value)>,
+    expected: Vec<String>,
+}
+
+fn samples(width: Width, signedness: Signedness) -> Vec<Value> {
+    match signedness {
+        Signedness::Signed => [
+            width.min_value(),
+            width.min_value() + 1,
+            -7,
+            -1,
+            0,
+            1,
+            3,
+            width.max_value(),
+        ]
+        .map(Value::Signed)
+        .to_vec(),
+        Signedness::Unsigned => {
+            let max = width.unsigned_max_value();
+            [0, 1, 3, 7, 200, max - 1, max]
+                .map(Value::Unsigned)
+                .to_vec()
+        }
+    }
+}
+
+fn cases(language: Language) -> Vec<Case> {
+    let mut cases = Vec::new();
+    for &(width, signedness, rust_type, c_type) in TYPES {
+        let values = samples(width, signedness);
+        let pairs: Vec<_> = values
+            .iter()
+            .zip(values.iter().rev())
+            .chain(values.iter().zip(values.iter().cycle().skip(1)))
+            .map(|(x, y)| (x.clone(), y.clone()))
+            .collect();
+        for &overflow in POLICIES {
+            let options = EvalOptions {
+                width,
+                signedness,
+                overflow,
+                ..EvalOptions::default()
+            };
+            for input in INPUTS {
+                let expected: Vec<_> = pairs
+                    .iter()
+                    .map(|(x, y)| {
+                        let mut context = Context::with_options(options);
+                        context.set("x", x.clone());
+                        context.set("y", y.clone());
+                        eval_in(&mut context, input)
+                            .map_or_else(|_| "error".to_string(), |value| value.to_string())
+                    })
+                    .collect();
+                match codegen(input, language, &options) {
+                    Ok(code) => cases.push(Case {
+                        code,
+                        width,
+                        signedness,
+                        rust_type,
+                        c_type,
+                        pairs: pairs.clone(),
+                        expected,
+                    }),
+                    // 生成時のエラーは評価でも必ずエラーになる
+                    Err(_) => assert!(expected.iter().all(|value| value == "error"), "{input}"),
+                }
+            }
+        }
+    }
+    cases
+}
+
+fn work_dir(language: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("calc_codegen_{language}_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn available(program: &str) -> bool {
+    if Command::new(program).arg("--version").output().is_ok() {
+        return true;
+    }
+    // 出力の捕捉を通らないよう標準エラーに直接書き、省略したことが見えるようにする
+    let _ = writeln!(std::io::stderr(), "skipped: {program} is not installed");
+    false
+}
+
+fn run(command: &mut Command) -> Output {
+    let output = command.output().unwrap();
+    assert!(
+        output.status.success(),
+        "{command:?} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn compare(cases: &[Case], stdout: &[u8], language: &str) {
+    let lines: Vec<_> = std::str::from_utf8(stdout).unwrap().lines().collect();
+    let mut lines = lines.into_iter();
+    for case in cases {
+        for (pair, expected) in case.pairs.iter().zip(&case.expected) {
+            let actual = lines.next().unwrap();
+            assert_eq!(
+                actual, expected,
+                "{language}: {} ({}) with (x, y) = {pair:?}\n{}",
+                case.code.expr, case.rust_type, case.code.helpers
+            );
+        }
+    }
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn rust_output_matches_eval() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let cases = cases(Language::Rust);
+    let mut source = String::from(
+        "#![allow(unused, unconditional_panic, arithmetic_overflow)]\n\
+         use std::panic::catch_unwind;\n",
+    );
+    for (index, case) in cases.iter().enumerate() {
+        let ty = case.rust_type;
+        writeln!(
+            source,
+            "mod case{index} {{\n{}\npub fn run(x: {ty}, y: {ty}) -> {ty} {{ {} }}\n}}",
+            case.code.helpers, case.code.expr
+        )
+        .unwrap();
+    }
+    source.push_str("fn main() {\n    std::panic::set_hook(Box::new(|_| {}));\n");
+    for (index, case) in cases.iter().enumerate() {
+        let pairs: Vec<_> = case
+            .pairs
+            .iter()
+            .map(|(x, y)| format!("({x}, {y})"))
+            .collect();
+        writeln!(
+            source,
+            "    for (x, y) in [{}] {{ match catch_unwind(|| case{index}::run(x, y)) {{ \
+             Ok(value) => println!(\"{{value}}\"), Err(_) => println!(\"error\") }} }}",
+            pairs.join(", ")
+        )
+        .unwrap();
+    }
+    source.push_str("}\n");
+
+    let dir = work_dir("rust");
+    let path = dir.join("main.rs");
+    fs::write(&path, source).unwrap();
+    let binary = dir.join("main");
+    run(Command::new(rustc)
+        .args(["--edition", "2021", "-o"])
+        .arg(&binary)
+        .arg(&path));
+    let output = run(&mut Command::new(&binary));
+    compare(&cases, &output.stdout, "Rust");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// cc がない環境では省略する。128-bit は GCC/Clang の __int128 を使う
+#[test]
+fn c_output_matches_eval() {
+    if !available("cc") {
+        return;
+    }
+    let cases = cases(Language::C);
+    let dir = work_dir("c");
+    let mut files = Vec::new();
+    // 補助関数は static なので式ごとに別の翻訳単位にする
+    for (index, case) in cases.iter().enumerate() {
+        let ty = case.c_type;
+        let path = dir.join(format!("case{index}.c"));
+        fs::write(
+            &path,
+            format!(
+                "#include <stdint.h>\n{}\n{ty} case{index}({ty} x, {ty} y) {{ return {}; }}\n",
+                case.code.helpers, case.code.expr
+            ),
+        )
+        .unwrap();
+        files.push(path);
+    }
+
+    // 範囲外は abort() するので 1 回ごとに子プロセスで実行する
+    let mut main = String::from(
+        "#include <stdint.h>\n#include <stdio.h>\n#include <unistd.h>\n#include <sys/wait.h>\n",
+    );
+    for (index, case) in cases.iter().enumerate() {
+        let ty = case.c_type;
+        writeln!(main, "{ty} case{index}({ty} x, {ty} y);").unwrap();
+    }
+    // printf は 128-bit の値を書けないので自前で 10 進にする
+    main.push_str(
+        "static void print_unsigned(unsigned __int128 value)\n\
+         {\n\
+         \x20   char buffer[40], *p = buffer + sizeof buffer;\n\
+         \x20   *--p = '\\0';\n\
+         \x20   do {\n\
+         \x20       *--p = (char)('0' + (int)(value % 10));\n\
+         \x20       value /= 10;\n\
+         \x20   } while (value != 0);\n\
+         \x20   printf(\"%s\\n\", p);\n\
+         }\n\
+         static void print_signed(__int128 value)\n\
+         {\n\
+         \x20   if (value < 0) {\n\
+         \x20       printf(\"-\");\n\
+         \x20       print_unsigned(-(unsigned __int128)value);\n\
+         \x20   } else {\n\
+         \x20       print_unsigned((unsigned __int128)value);\n\
+         \x20   }\n\
+         }\n\
+         #define RUN(call, print) do { \\\n\
+         fflush(stdout); \\\n\
+         pid_t pid = fork(); \\\n\
+         if (pid == 0) { print(call); fflush(stdout); _exit(0); } \\\n\
+         int status; waitpid(pid, &status, 0); \\\n\
+         if (!WIFEXITED(status) || WEXITSTATUS(status) != 0) printf(\"error\\n\"); \\\n\
+         } while (0)\n\
+         int main(void)\n{\n",
+    );
+    for (index, case) in cases.iter().enumerate() {
+        let print = match case.signedness {
+            Signedness::Signed => "print_signed",
+            Signedness::Unsigned => "print_unsigned",
+        };
+        let ty = case.c_type;
+        let pairs: Vec<_> = case
+            .pairs
+            .iter()
+            .map(|(x, y)| format!("{{{}, {}}}", c_value(x), c_value(y)))
+            .collect();
+        writeln!(
+            main,
+            "    {{\n\
+             \x20       static const unsigned __int128 pairs[][2] = {{{}}};\n\
+             \x20       for (size_t i = 0; i < sizeof pairs / sizeof pairs[0]; i++)\n\
+             \x20           RUN(case{index}(({ty})pairs[i][0], ({ty})pairs[i][1]), {print});\n\
+             \x20   }}",
+            pairs.join(", ")
+        )
+        .unwrap();
+    }
+    main.push_str("    return 0;\n}\n");
+    let main_path = dir.join("main.c");
+    fs::write(&main_path, main).unwrap();
+    files.push(main_path);
+
+    let binary = dir.join("main");
+    run(Command::new("cc")
+        .args(["-std=c11", "-o"])
+        .arg(&binary)
+        .args(&files));
+    let output = run(&mut Command::new(&binary));
+    compare(&cases, &output.stdout, "C");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// 最小値もそのまま書けるよう 2 の補数のビット列で渡す。C のリテラルは 64 bit までなので上位と下位から組み立てる
+fn c_value(value: &Value) -> String {
+    let bits = bit_pattern(value);
+    format!(
+        "(((unsigned __int128)0x{:X}ULL << 64) | 0x{:X}ULL)",
+        bits >> 64,
+        bits as u64
+    )
+}
+
+fn bit_pattern(value: &Value) -> u128 {
+    value
+        .as_i128()
+        .map_or_else(|| value.as_u128().unwrap(), |value| value as u128)
+}
+
+// python3 がない環境では省略する
+#[test]
+fn python_output_matches_eval() {
+    if !available("python3") {
+        return;
+    }
+    let cases = cases(Language::Python);
+    let mut script = String::from(
+        "def run(helpers, expr, pairs):\n\
+         \x20   namespace = {}\n\
+         \x20   exec(helpers, namespace)\n\
+         \x20   for x, y in pairs:\n\
+         \x20       namespace.update(x=x, y=y)\n\
+         \x20       try:\n\
+         \x20           print(eval(expr, namespace))\n\
+         \x20       except (OverflowError, ZeroDivisionError, ValueError):\n\
+         \x20           print('error')\n\n",
+    );
+    for case in &cases {
+        let pairs: Vec<_> = case
+            .pairs
+            .iter()
+            .map(|(x, y)| format!("({x}, {y})"))
+            .collect();
+        writeln!(
+            script,
+            "run({:?}, {:?}, [{}])",
+            case.code.helpers,
+            case.code.expr,
+            pairs.join(", ")
+        )
+        .unwrap();
+    }
+
+    let dir = work_dir("python");
+    let path = dir.join("main.py");
+    fs::write(&path, script).unwrap();
+    let output = run(Command::new("python3").arg(&path));
+    compare(&cases, &output.stdout, "Python");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// iverilog がない環境では省略する。式ごとにモジュールを作り、エラー（'x）は error と出力する
+#[test]
+fn verilog_output_matches_eval() {
+    if !available("iverilog") {
+        return;
+    }
+    let cases = cases(Language::Verilog);
+    let mut source = String::new();
+    for (index, case) in cases.iter().enumerate() {
+        let port = verilog_type(case);
+        writeln!(
+            source,
+            "module case{index}(input {port} x, input {port} y, output {port} r);\n\
+             {}\n\
+             assign r = {};\n\
+             endmodule",
+            case.code.helpers, case.code.expr
+        )
+        .unwrap();
+    }
+    source.push_str("module main;\n");
+    for (index, case) in cases.iter().enumerate() {
+        let port = verilog_type(case);
+        writeln!(
+            source,
+            "reg {port} x{index}, y{index};\n\
+             wire {port} r{index};\n\
+             case{index} c{index}(x{index}, y{index}, r{index});"
+        )
+        .unwrap();
+    }
+    source.push_str("initial begin\n");
+    for (index, case) in cases.iter().enumerate() {
+        let bits = case.width.bits();
+        let mask = case.width.unsigned_max_value();
+        for (x, y) in &case.pairs {
+            writeln!(
+                source,
+                "    x{index} = {bits}'h{:X}; y{index} = {bits}'h{:X}; #1;\n\
+                 \x20   if (^r{index} === 1'bx) $display(\"error\"); else $display(\"%0d\", r{index});",
+                bit_pattern(x) & mask,
+                bit_pattern(y) & mask
+            )
+            .unwrap();
+        }
+    }
+    source.push_str("end\nendmodule\n");
+
+    let dir = work_dir("verilog");
+    let path = dir.join("main.v");
+    fs::write(&path, source).unwrap();
+    let binary = dir.join("main.vvp");
+    run(Command::new("iverilog")
+        .args(["-g2012", "-o"])
+        .arg(&binary)
+        .arg(&path));
+    let output = run(Command::new("vvp").arg("-n").arg(&binary));
+    compare(&cases, &output.stdout, "Verilog");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+fn verilog_type(case: &Case) -> String {
+    let signed = match case.signedness {
+        Signedness::Signed => "signed ",
+        Signedness::Unsigned => "",
+    };
+    format!("{signed}[{}:0]", case.width.bits() - 1)
+}
@@ -0,0 +1,9 @@
+use calc_core::{ErrorKind, Operation, Value};
+
+// 演算と被演算子からオーバーフローのエラーを作る
+pub fn overflow(op: impl Into<Operation>, operands: &[i128]) -> ErrorKind {
+    ErrorKind::Overflow {
+        op: op.into(),
+        operands: operands.iter().map(|&value| Value::from(value)).collect(),
+    }
+}
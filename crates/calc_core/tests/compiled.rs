@@ -0,0 +1,121 @@
+use calc_core::{
+    parse_ast, BinaryOp, CalcError, CompiledExpr, Context, ErrorKind, EvalOptions, Operation,
+    OverflowPolicy, Precision, Signedness, Span, Value, Width,
+};
+
+fn all_options() -> Vec<EvalOptions> {
+    let mut options = Vec::new();
+    for width in [Width::W8, Width::W32, Width::W128] {
+        for overflow in [
+            OverflowPolicy::Checked,
+            OverflowPolicy::Wrapping,
+            OverflowPolicy::Saturating,
+        ] {
+            for signedness in [Signedness::Signed, Signedness::Unsigned] {
+                options.push(EvalOptions {
+                    width,
+                    overflow,
+                    signedness,
+                    ..EvalOptions::default()
+                });
+            }
+        }
+    }
+    options.push(EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    });
+    options
+}
+
+// インタプリタと同じ結果・同じエラー
+#[test]
+fn compiled_matches_interpreter() {
+    let inputs = [
+        "1 + 2 * 3 - 4",
+        "0x7F + 1",
+        "-128 - 1",
+        "-(0xFF) + -0xFF",
+        "~x ^ y << 2",
+        "(x * y) / (y - y)",
+        "x % 0",
+        "2 ** 7 + -2 ** 7",
+        "max(x, y) rotl 3 >>> 1",
+        "abs(-x) - popcount(y)",
+        "z + 1",
+        "1 << 200",
+    ];
+    for options in all_options() {
+        let mut ctx = Context::with_options(options);
+        ctx.set("x", 100);
+        ctx.set("y", 3);
+        for input in inputs {
+            let expr = parse_ast(input).unwrap();
+            let compiled = CompiledExpr::new(input).unwrap();
+            assert_eq!(
+                compiled.eval_in(&ctx),
+                expr.eval_in(&ctx),
+                "{input} with {options:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn compiled_parse_errors() {
+    assert_eq!(
+        CompiledExpr::new("1 +").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('+'))
+    );
+}
+
+// 変数の束縛を変えて何度も評価する
+#[test]
+fn compiled_variables_in_order() {
+    let compiled = CompiledExpr::new("base + index * 4 + base").unwrap();
+    assert_eq!(compiled.variables(), ["base", "index"]);
+
+    let options = EvalOptions::default();
+    for index in 0..100 {
+        assert_eq!(
+            compiled.eval_with(&options, &[Value::from(0x1000), Value::from(index)]),
+            Ok(Value::from(0x2000 + index * 4))
+        );
+    }
+}
+
+#[test]
+fn compiled_binding_errors() {
+    let compiled = CompiledExpr::new("1 + (a * b)").unwrap();
+    let w8 = EvalOptions {
+        width: Width::W8,
+        ..EvalOptions::default()
+    };
+    assert_eq!(
+        compiled.eval_with(&w8, &[Value::from(16), Value::from(16)]),
+        Err(CalcError::new(
+            ErrorKind::Overflow {
+                op: Operation::Binary(BinaryOp::Mul),
+                operands: vec![Value::from(16), Value::from(16)],
+            },
+            Span::new(5, 10)
+        ))
+    );
+    assert_eq!(
+        compiled.eval_with(&w8, &[Value::from(1)]),
+        Err(CalcError::new(
+            ErrorKind::UnknownIdentifier("b".to_string()),
+            Span::new(9, 10)
+        ))
+    );
+    assert_eq!(
+        compiled
+            .eval_with(&w8, &[Value::from(1000), Value::from(1)])
+            .map_err(CalcError::into_kind),
+        Err(ErrorKind::VariableOutOfRange("a".to_string()))
+    );
+    assert_eq!(
+        compiled.eval(&w8).map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownIdentifier("a".to_string()))
+    );
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use calc_core::{eval_expression, eval_in, BinaryOp, CalcError, Context, ErrorKind, Value};
+use common::overflow;
+
+// 代入と参照
+#[test]
+fn context_assignment_returns_value() {
+    let mut ctx = Context::new();
+    assert_eq!(eval_in(&mut ctx, "x = 0x40"), Ok(Value::from(0x40)));
+    assert_eq!(ctx.get("x"), Some(Value::from(0x40)));
+}
+
+#[test]
+fn context_variable_lookup() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "base = 0x1000").unwrap();
+    eval_in(&mut ctx, "stride = 16").unwrap();
+    assert_eq!(
+        eval_in(&mut ctx, "base + 3 * stride"),
+        Ok(Value::from(0x1030))
+    );
+}
+
+#[test]
+fn context_reassignment_uses_previous_value() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "x = 1").unwrap();
+    assert_eq!(eval_in(&mut ctx, "x = x << 4"), Ok(Value::from(16)));
+    assert_eq!(ctx.get("x"), Some(Value::from(16)));
+}
+
+#[test]
+fn context_identifier_with_digits_and_underscore() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "reg_0x = 3").unwrap();
+    assert_eq!(eval_in(&mut ctx, "-reg_0x**2"), Ok(Value::from(-9)));
+}
+
+#[test]
+fn context_set_from_host() {
+    let mut ctx = Context::new();
+    ctx.set("mask", 0xFF);
+    assert_eq!(eval_in(&mut ctx, "0x1234 & mask"), Ok(Value::from(0x34)));
+    assert_eq!(ctx.remove("mask"), Some(Value::from(0xFF)));
+    assert_eq!(
+        eval_in(&mut ctx, "mask").map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownIdentifier("mask".to_string()))
+    );
+}
+
+// ans
+#[test]
+fn context_ans_holds_previous_result() {
+    let mut ctx = Context::new();
+    assert_eq!(ctx.ans(), None);
+    eval_in(&mut ctx, "6 * 7").unwrap();
+    assert_eq!(ctx.ans(), Some(Value::from(42)));
+    assert_eq!(eval_in(&mut ctx, "ans + 1"), Ok(Value::from(43)));
+    assert_eq!(eval_in(&mut ctx, "ans * 2"), Ok(Value::from(86)));
+}
+
+#[test]
+fn context_ans_updated_by_assignment() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "x = 5").unwrap();
+    assert_eq!(eval_in(&mut ctx, "ans"), Ok(Value::from(5)));
+}
+
+#[test]
+fn context_error_leaves_state_unchanged() {
+    let mut ctx = Context::new();
+    eval_in(&mut ctx, "x = 1").unwrap();
+    assert_eq!(
+        eval_in(&mut ctx, "x = 2147483647 + 1").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[2147483647, 1]))
+    );
+    assert_eq!(ctx.get("x"), Some(Value::from(1)));
+    assert_eq!(ctx.ans(), Some(Value::from(1)));
+}
+
+// 未定義の識別子
+#[test]
+fn context_unknown_identifier_error() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_in(&mut ctx, "y + 1").map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownIdentifier("y".to_string()))
+    );
+}
+
+#[test]
+fn stateless_eval_has_no_ans() {
+    assert_eq!(
+        eval_expression("ans").map_err(CalcError::into_kind),
+        Err(ErrorKind::UnknownIdentifier("ans".to_string()))
+    );
+}
+
+// 代入の異常系
+#[test]
+fn assignment_missing_value_error() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_in(&mut ctx, "x =").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('='))
+    );
+}
+
+#[test]
+fn assignment_invalid_target_error() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_in(&mut ctx, "1 = 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('='))
+    );
+    assert_eq!(
+        eval_in(&mut ctx, "x = 1 = 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('='))
+    );
+}
+
+#[test]
+fn keyword_is_not_identifier() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_in(&mut ctx, "rotl = 1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('r'))
+    );
+}
@@ -0,0 +1,151 @@
+use calc_core::{
+    eval_expression, eval_in, eval_with, format_all_with, format_radix, parse, CalcError, Context,
+    ErrorKind, EvalOptions, FormatOptions, Signedness, Span, Width,
+};
+
+fn span_of(result: Result<impl std::fmt::Debug, CalcError>) -> Option<Span> {
+    result.unwrap_err().span()
+}
+
+// 構文エラーの位置
+#[test]
+fn span_of_invalid_token() {
+    assert_eq!(span_of(eval_expression("1 + 2 # 3")), Some(Span::new(6, 7)));
+    assert_eq!(span_of(eval_expression("1 + * 2")), Some(Span::new(4, 5)));
+    assert_eq!(span_of(eval_expression("1 +")), Some(Span::new(2, 3)));
+    assert_eq!(span_of(eval_expression("1 >>>")), Some(Span::new(2, 5)));
+}
+
+#[test]
+fn span_of_missing_paren() {
+    assert_eq!(span_of(eval_expression("(1 + 2")), Some(Span::new(6, 6)));
+    assert_eq!(span_of(eval_expression("(1 2)")), Some(Span::new(3, 4)));
+    assert_eq!(span_of(eval_expression("()")), Some(Span::new(1, 2)));
+}
+
+#[test]
+fn span_of_invalid_literal() {
+    assert_eq!(
+        span_of(eval_expression("0xFF + 0x1G")),
+        Some(Span::new(7, 11))
+    );
+    assert_eq!(span_of(parse("  0b102")), Some(Span::new(2, 7)));
+    assert_eq!(span_of(eval_expression("")), Some(Span::new(0, 0)));
+}
+
+#[test]
+fn span_of_unknown_names() {
+    assert_eq!(
+        span_of(eval_expression("1 + foo(2)")),
+        Some(Span::new(4, 7))
+    );
+    assert_eq!(span_of(eval_expression("2 * x")), Some(Span::new(4, 5)));
+}
+
+// 評価エラーは部分式の位置
+#[test]
+fn span_of_evaluation_errors() {
+    assert_eq!(
+        span_of(eval_expression("1 + (2147483647 + 1)")),
+        Some(Span::new(5, 19))
+    );
+    assert_eq!(
+        span_of(eval_expression("8 / (2 - 2) + 1")),
+        Some(Span::new(0, 11))
+    );
+    assert_eq!(span_of(eval_expression("1 << 32")), Some(Span::new(0, 7)));
+    assert_eq!(span_of(eval_expression("min(1)")), Some(Span::new(0, 6)));
+    let w8 = EvalOptions {
+        width: Width::W8,
+        ..EvalOptions::default()
+    };
+    assert_eq!(span_of(eval_with("1 + 0x100", &w8)), Some(Span::new(4, 9)));
+    assert_eq!(span_of(eval_with("-0x81", &w8)), Some(Span::new(0, 5)));
+}
+
+#[test]
+fn format_errors_have_no_span() {
+    let options = FormatOptions::default();
+    assert_eq!(span_of(format_radix(1, 37, &options)), None);
+}
+
+#[test]
+fn error_kind_and_display() {
+    let err = eval_expression("1 # 2").unwrap_err();
+    assert_eq!(err.kind(), &ErrorKind::InvalidToken('#'));
+    assert_eq!(err.to_string(), "invalid token '#'");
+}
+
+// 範囲エラーの説明
+#[test]
+fn display_range_errors() {
+    let message = |input| eval_expression(input).unwrap_err().to_string();
+    assert_eq!(message("8 / (2 - 2)"), "division by zero");
+    assert_eq!(message("8 % 0"), "remainder by zero");
+    assert_eq!(
+        message("2147483647 + 1"),
+        "overflow in addition: 2147483647 + 1 is out of range"
+    );
+    assert_eq!(
+        message("-(-2147483647 - 1)"),
+        "overflow in negation: -(-2147483648) is out of range"
+    );
+    assert_eq!(
+        message("abs(-2147483648)"),
+        "overflow in function 'abs': abs(-2147483648) is out of range"
+    );
+    assert_eq!(
+        message("2 ** 40"),
+        "overflow in exponentiation: 2 ** 40 is out of range"
+    );
+}
+
+#[test]
+fn display_out_of_range_values() {
+    let u8 = EvalOptions {
+        width: Width::W8,
+        signedness: Signedness::Unsigned,
+        ..EvalOptions::default()
+    };
+    assert_eq!(
+        format_all_with(-1, &u8).unwrap_err().to_string(),
+        "result -1 is out of range"
+    );
+    let mut ctx = Context::with_options(u8);
+    ctx.set("x", 256);
+    assert_eq!(
+        eval_in(&mut ctx, "x + 1").unwrap_err().to_string(),
+        "value of 'x' does not fit the current width"
+    );
+}
+
+// キャレット付きの表示
+#[test]
+fn render_caret_under_span() {
+    let input = "0xFF + 0x1G";
+    let err = eval_expression(input).unwrap_err();
+    assert_eq!(
+        err.render(input),
+        "0xFF + 0x1G\n       ^^^^ invalid literal"
+    );
+}
+
+#[test]
+fn render_caret_at_end_of_input() {
+    let input = "(1 + 2";
+    let err = eval_expression(input).unwrap_err();
+    assert_eq!(err.render(input), "(1 + 2\n      ^ invalid token ')'");
+}
+
+#[test]
+fn render_counts_characters_and_keeps_tabs() {
+    let input = "'é' +\t#";
+    let err = eval_expression(input).unwrap_err();
+    assert_eq!(err.render(input), "'é' +\t#\n     \t^ invalid token '#'");
+}
+
+#[test]
+fn render_without_span() {
+    let err = format_radix(1, 37, &FormatOptions::default()).unwrap_err();
+    assert_eq!(err.render("ignored"), "radix must be between 2 and 36");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use calc_core::{eval_expression, BinaryOp, CalcError, ErrorKind};
+use common::overflow;
+
+// 除算
+#[test]
+fn division_basic() {
+    assert_eq!(eval_expression("7/2"), Ok(3));
+}
+
+#[test]
+fn division_exact() {
+    assert_eq!(eval_expression("0x40 / 0b100"), Ok(16));
+}
+
+// 除算は Euclidean（余り % と整合する）
+#[test]
+fn division_negative_dividend_rounds_down() {
+    assert_eq!(eval_expression("-7/2"), Ok(-4));
+}
+
+#[test]
+fn division_negative_divisor_rounds_toward_zero() {
+    assert_eq!(eval_expression("7/-2"), Ok(-3));
+}
+
+#[test]
+fn division_both_negative() {
+    assert_eq!(eval_expression("-7/-2"), Ok(4));
+}
+
+#[test]
+fn division_remainder_identity() {
+    for (a, b) in [(7, 2), (-7, 2), (7, -2), (-7, -2), (-2147483648, 3)] {
+        let q = eval_expression(&format!("{a}/{b}")).unwrap();
+        let r = eval_expression(&format!("{a}%{b}")).unwrap();
+        assert_eq!(q * b + r, a, "a={a} b={b}");
+        assert!(0 <= r && r < b.abs());
+    }
+}
+
+// 優先順位
+#[test]
+fn division_same_precedence_as_multiplication() {
+    assert_eq!(eval_expression("8/2*3"), Ok(12));
+    assert_eq!(eval_expression("1+8/2"), Ok(5));
+}
+
+// 0除算
+#[test]
+fn division_by_zero_error() {
+    assert_eq!(
+        eval_expression("5/0").map_err(CalcError::into_kind),
+        Err(ErrorKind::DivisionByZero)
+    );
+}
+
+// オーバーフロー
+#[test]
+fn division_min_by_minus_one_range_error() {
+    assert_eq!(
+        eval_expression("-2147483648 / -1").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Div, &[-2147483648, -1]))
+    );
+}
+
+// 演算子の異常系
+#[test]
+fn trailing_division_operator_error() {
+    assert_eq!(
+        eval_expression("1/").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('/'))
+    );
+}
+
+#[test]
+fn consecutive_division_operator_error() {
+    assert_eq!(
+        eval_expression("1//2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('/'))
+    );
+}
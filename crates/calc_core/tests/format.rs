@@ -1,4 +1,7 @@
-use calc_core::{format_all, CalcError, FormattedValue};
+use calc_core::{
+    format_all, format_all_with, format_radix_with, CalcError, ErrorKind, EvalOptions,
+    FormatOptions, FormattedValue, NegativeStyle, Value, Width,
+};
 
 // フォーマット
 #[test]
@@ -7,8 +10,12 @@ fn format_zero() {
         format_all(0),
         Ok(FormattedValue {
             bin: "0b0".to_string(),
+            oct: "0o0".to_string(),
             dec: "0".to_string(),
             hex: "0x0".to_string(),
+            bin_twos: "0b0".to_string(),
+            oct_twos: "0o0".to_string(),
+            hex_twos: "0x0".to_string(),
         })
     );
 }
@@ -19,8 +26,12 @@ fn format_positive_value() {
         format_all(26),
         Ok(FormattedValue {
             bin: "0b1_1010".to_string(),
+            oct: "0o32".to_string(),
             dec: "26".to_string(),
             hex: "0x1A".to_string(),
+            bin_twos: "0b1_1010".to_string(),
+            oct_twos: "0o32".to_string(),
+            hex_twos: "0x1A".to_string(),
         })
     );
 }
@@ -31,13 +42,68 @@ fn format_negative_value() {
         format_all(-26),
         Ok(FormattedValue {
             bin: "-0b1_1010".to_string(),
+            oct: "-0o32".to_string(),
             dec: "-26".to_string(),
             hex: "-0x1A".to_string(),
+            bin_twos: "0b1111_1111_1111_1111_1111_1111_1110_0110".to_string(),
+            oct_twos: "0o37777777746".to_string(),
+            hex_twos: "0xFFFFFFE6".to_string(),
         })
     );
 }
 
+#[test]
+fn format_octal_permissions() {
+    assert_eq!(format_all(0o755).map(|f| f.oct), Ok("0o755".to_string()));
+}
+
 #[test]
 fn format_out_of_range_error() {
-    assert_eq!(format_all(2147483648), Err(CalcError::RangeError));
+    assert_eq!(
+        format_all(2147483648).map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(2147483648i64)))
+    );
+}
+
+// 2 の補数表示
+#[test]
+fn format_twos_complement_follows_width() {
+    let w8 = EvalOptions {
+        width: Width::W8,
+        ..EvalOptions::default()
+    };
+    let formatted = format_all_with(-1, &w8).unwrap();
+    assert_eq!(formatted.hex, "-0x1");
+    assert_eq!(formatted.hex_twos, "0xFF");
+    assert_eq!(formatted.bin_twos, "0b1111_1111");
+    assert_eq!(formatted.oct_twos, "0o377");
+    assert_eq!(formatted.dec, "-1");
+}
+
+#[test]
+fn format_radix_twos_complement() {
+    let twos = FormatOptions {
+        negative: NegativeStyle::TwosComplement,
+        ..FormatOptions::default()
+    };
+    let w16 = EvalOptions {
+        width: Width::W16,
+        ..EvalOptions::default()
+    };
+    assert_eq!(
+        format_radix_with(-26, 16, &twos, &w16),
+        Ok("0xFFE6".to_string())
+    );
+    assert_eq!(
+        format_radix_with(26, 16, &twos, &w16),
+        Ok("0x1A".to_string())
+    );
+    assert_eq!(
+        format_radix_with(-1, 10, &twos, &w16),
+        Ok("65535".to_string())
+    );
+    assert_eq!(
+        format_radix_with(-26, 16, &FormatOptions::default(), &w16),
+        Ok("-0x1A".to_string())
+    );
 }
@@ -0,0 +1,262 @@
+use calc_core::{
+    codegen, eval_expression, eval_traced, eval_with, parse_ast, parse_ast_recovering,
+    parse_ast_recovering_with, parse_ast_with, pretty_print, pretty_print_with, tokenize,
+    CalcError, CompiledExpr, ErrorKind, EvalOptions, Language, PrettyOptions, Span, TokenKind,
+    Value,
+};
+
+fn eval(input: &str) -> Result<Value, (ErrorKind, Option<Span>)> {
+    eval_with(input, &EvalOptions::default()).map_err(|err| (err.kind().clone(), err.span()))
+}
+
+fn limited(max_depth: usize) -> EvalOptions {
+    EvalOptions {
+        max_depth,
+        ..EvalOptions::default()
+    }
+}
+
+fn parens(depth: usize, inner: &str) -> String {
+    format!("{}{inner}{}", "(".repeat(depth), ")".repeat(depth))
+}
+
+// 深すぎる入力はスタックを使い切る前にエラーにする
+#[test]
+fn deeply_nested_input_is_rejected() {
+    let n = 300;
+    let inputs = [
+        parens(n, "1"),
+        format!("{}1", "-".repeat(n)),
+        format!("{}1", "~".repeat(n)),
+        format!("{}1", "(-".repeat(n)),
+        format!("{}1{}", "(1+".repeat(n), ")".repeat(n)),
+        format!("2{}", " ** 1".repeat(n)),
+        format!("{}1{}", "max(0, ".repeat(n), ")".repeat(n)),
+    ];
+    let options = EvalOptions::default();
+    let expected = Some(ErrorKind::NestingTooDeep(100));
+    for input in &inputs {
+        assert_eq!(
+            eval_with(input, &options).err().map(CalcError::into_kind),
+            expected
+        );
+        assert_eq!(
+            eval_traced(input, &options).err().map(CalcError::into_kind),
+            expected
+        );
+        assert_eq!(parse_ast(input).err().map(CalcError::into_kind), expected);
+        assert_eq!(
+            pretty_print(input, &PrettyOptions::default())
+                .err()
+                .map(CalcError::into_kind),
+            expected
+        );
+        assert_eq!(
+            codegen(input, Language::Rust, &options)
+                .err()
+                .map(CalcError::into_kind),
+            expected
+        );
+    }
+}
+
+// 長すぎる入力は解析する前にエラーにする
+#[test]
+fn long_input_is_rejected() {
+    let inputs = [
+        parens(100_000, "1"),
+        format!("{}1", "-".repeat(100_000)),
+        format!("1{}", " + 1".repeat(100_000)),
+    ];
+    let options = EvalOptions::default();
+    for input in &inputs {
+        let span = tokenize(input)
+            .into_iter()
+            .filter(|token| token.kind != TokenKind::Whitespace)
+            .nth(2000)
+            .map(|token| token.span);
+        let expected = Some((ErrorKind::ExpressionTooLong(2000), span));
+        let kind = |err: CalcError| (err.kind().clone(), err.span());
+        assert_eq!(eval_with(input, &options).err().map(kind), expected);
+        assert_eq!(eval_traced(input, &options).err().map(kind), expected);
+        assert_eq!(parse_ast(input).err().map(kind), expected);
+        assert_eq!(CompiledExpr::new(input).err().map(kind), expected);
+        assert_eq!(
+            codegen(input, Language::Rust, &options).err().map(kind),
+            expected
+        );
+        let partial = parse_ast_recovering(input);
+        assert!(partial.exprs.is_empty());
+        assert_eq!(
+            partial
+                .diagnostics
+                .into_iter()
+                .map(kind)
+                .collect::<Vec<_>>(),
+            expected.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    let err = eval_with(&inputs[2], &options).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expression is too long (limit 2000 tokens)"
+    );
+}
+
+// エラーの位置は上限を超えた括弧や演算子
+#[test]
+fn error_points_at_token_beyond_limit() {
+    assert_eq!(
+        eval(&parens(101, "1")),
+        Err((ErrorKind::NestingTooDeep(100), Some(Span::new(100, 101))))
+    );
+    assert_eq!(
+        eval(&format!("{}1", "-".repeat(101))),
+        Err((ErrorKind::NestingTooDeep(100), Some(Span::new(100, 101))))
+    );
+
+    let input = format!("{}1", "-".repeat(101));
+    let err = eval_with(&input, &EvalOptions::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expression is nested too deeply (limit 100)"
+    );
+}
+
+// 上限ちょうどまでは評価できる
+#[test]
+fn nesting_up_to_limit() {
+    assert_eq!(eval(&parens(100, "7")), Ok(Value::from(7)));
+    assert_eq!(eval(&format!("{}1", "-".repeat(100))), Ok(Value::from(1)));
+
+    // 右辺が演算子を含む式なら 1 段深くなる
+    assert_eq!(eval(&parens(99, "1 + 2 * 3")), Ok(Value::from(7)));
+    assert_eq!(
+        eval(&parens(100, "1 + 2 * 3")).map_err(|(kind, _)| kind),
+        Err(ErrorKind::NestingTooDeep(100))
+    );
+}
+
+// 左結合の連鎖は入れ子に数えず、長さの上限までたどれる
+#[test]
+fn long_chains_are_not_nested() {
+    let sum = format!("1{}", " + 1".repeat(150));
+    assert_eq!(eval_expression(&sum), Ok(151));
+    assert_eq!(
+        CompiledExpr::new(&sum).and_then(|compiled| compiled.eval(&EvalOptions::default())),
+        Ok(Value::from(151))
+    );
+    assert_eq!(eval(&parens(100, &sum)), Ok(Value::from(151)));
+
+    // 上限ちょうどのトークン数
+    let long = format!("1{}", " + 1".repeat(999));
+    assert_eq!(eval(&long), Ok(Value::from(1000)));
+    assert_eq!(
+        CompiledExpr::new(&long).and_then(|compiled| compiled.eval(&EvalOptions::default())),
+        Ok(Value::from(1000))
+    );
+    // 構文木の高さも抑えられるので、導出した Clone などでスタックを使い切らない
+    let ast = parse_ast(&long).unwrap();
+    assert_eq!(ast.clone(), ast);
+    assert!(format!("{ast:?}").starts_with("Binary"));
+
+    let mixed = format!("2{}", " * 3 - 5".repeat(499));
+    let options = EvalOptions::default();
+    assert_eq!(
+        eval_traced(&mixed, &options).map(|steps| steps.len()),
+        Ok(998)
+    );
+    assert_eq!(
+        pretty_print(&mixed, &PrettyOptions::default()),
+        Ok(mixed.clone())
+    );
+    assert!(codegen(&mixed, Language::C, &options).is_ok());
+}
+
+// 上限は EvalOptions で変えられる
+#[test]
+fn configurable_limit() {
+    assert_eq!(
+        eval_with("((1))", &limited(1)).map_err(CalcError::into_kind),
+        Err(ErrorKind::NestingTooDeep(1))
+    );
+    assert_eq!(eval_with("(1) + 2", &limited(2)), Ok(Value::from(3)));
+    assert_eq!(eval_with("42", &limited(0)), Ok(Value::from(42)));
+    assert_eq!(
+        eval_with("-1", &limited(0)).map_err(CalcError::into_kind),
+        Err(ErrorKind::NestingTooDeep(0))
+    );
+
+    let long = format!("1{}", " + 1".repeat(299));
+    assert_eq!(eval_with(&long, &limited(1)), Ok(Value::from(300)));
+
+    let short = EvalOptions {
+        max_tokens: 3,
+        ..EvalOptions::default()
+    };
+    assert_eq!(eval_with("1 + 2", &short), Ok(Value::from(3)));
+    assert_eq!(
+        eval_with("1 + 2 + 3", &short).map_err(CalcError::into_kind),
+        Err(ErrorKind::ExpressionTooLong(3))
+    );
+}
+
+// 解析だけの API やバッチ評価も上限を指定できる
+#[test]
+fn parse_with_limit() {
+    let deep = parens(101, "1");
+    let expected = Some(ErrorKind::NestingTooDeep(100));
+    assert_eq!(parse_ast(&deep).err().map(CalcError::into_kind), expected);
+    assert_eq!(
+        CompiledExpr::new(&deep).err().map(CalcError::into_kind),
+        expected
+    );
+
+    assert!(parse_ast_with(&deep, &limited(101)).is_ok());
+    assert_eq!(
+        parse_ast_recovering_with(&deep, &limited(101)).diagnostics,
+        Vec::new()
+    );
+    assert_eq!(
+        pretty_print_with(&deep, &PrettyOptions::default(), &limited(101)),
+        Ok("1".to_string())
+    );
+    assert_eq!(
+        CompiledExpr::new_with(&deep, &limited(101))
+            .and_then(|compiled| compiled.eval(&EvalOptions::default())),
+        Ok(Value::from(1))
+    );
+    assert_eq!(
+        parse_ast_with("((1))", &limited(1)).map_err(CalcError::into_kind),
+        Err(ErrorKind::NestingTooDeep(1))
+    );
+}
+
+// 回復付きの解析は深すぎる所で打ち切り、エラーは 1 つだけ報告する
+#[test]
+fn recovering_stops_at_limit() {
+    let partial = parse_ast_recovering(&parens(500, "1"));
+    assert!(partial.exprs.is_empty());
+    assert_eq!(
+        partial.diagnostics,
+        vec![CalcError::new(
+            ErrorKind::NestingTooDeep(100),
+            Span::new(100, 101)
+        )]
+    );
+
+    let input = format!("1 # {}", parens(500, "1"));
+    let diagnostics: Vec<_> = parse_ast_recovering(&input)
+        .diagnostics
+        .into_iter()
+        .map(|err| (err.kind().clone(), err.span()))
+        .collect();
+    assert_eq!(
+        diagnostics,
+        vec![
+            (ErrorKind::InvalidToken('#'), Some(Span::new(2, 3))),
+            (ErrorKind::NestingTooDeep(100), Some(Span::new(104, 105))),
+        ]
+    );
+}
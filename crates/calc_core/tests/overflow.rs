@@ -0,0 +1,211 @@
+mod common;
+
+use calc_core::{
+    eval_with, BinaryOp, CalcError, ErrorKind, EvalOptions, OverflowPolicy, Signedness, Value,
+    Width,
+};
+use common::overflow;
+
+fn opts(overflow: OverflowPolicy) -> EvalOptions {
+    EvalOptions {
+        overflow,
+        ..EvalOptions::default()
+    }
+}
+
+fn unsigned(width: Width, overflow: OverflowPolicy) -> EvalOptions {
+    EvalOptions {
+        width,
+        signedness: Signedness::Unsigned,
+        overflow,
+        ..EvalOptions::default()
+    }
+}
+
+// 既定は checked
+#[test]
+fn overflow_default_is_checked() {
+    let checked = opts(OverflowPolicy::Checked);
+    assert_eq!(EvalOptions::default().overflow, OverflowPolicy::Checked);
+    assert_eq!(
+        eval_with("2147483647 + 1", &checked).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[2147483647, 1]))
+    );
+    assert_eq!(
+        eval_with("0xFFFFFFFF", &checked).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+// wrapping
+#[test]
+fn overflow_wrapping_arithmetic() {
+    let wrapping = opts(OverflowPolicy::Wrapping);
+    assert_eq!(
+        eval_with("2147483647 + 1", &wrapping),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("-2147483648 - 1", &wrapping),
+        Ok(Value::from(i32::MAX))
+    );
+    assert_eq!(
+        eval_with("0x10000 * 0x10000", &wrapping),
+        Ok(Value::from(0))
+    );
+    assert_eq!(
+        eval_with("0x10001 * 0x10001", &wrapping),
+        Ok(Value::from(0x20001))
+    );
+    assert_eq!(
+        eval_with("-2147483648 / -1", &wrapping),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(eval_with("2**32 + 5", &wrapping), Ok(Value::from(5)));
+}
+
+#[test]
+fn overflow_wrapping_negation() {
+    let wrapping = opts(OverflowPolicy::Wrapping);
+    assert_eq!(
+        eval_with("-(-2147483648)", &wrapping),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("-1", &unsigned(Width::W8, OverflowPolicy::Wrapping)),
+        Ok(Value::from(0xFFu8))
+    );
+}
+
+#[test]
+fn overflow_wrapping_literals() {
+    let wrapping = opts(OverflowPolicy::Wrapping);
+    assert_eq!(eval_with("0xFFFFFFFF", &wrapping), Ok(Value::from(-1)));
+    assert_eq!(eval_with("0x1_0000_0001", &wrapping), Ok(Value::from(1)));
+    assert_eq!(
+        eval_with("-2147483649", &wrapping),
+        Ok(Value::from(i32::MAX))
+    );
+    assert_eq!(
+        eval_with(
+            "200",
+            &EvalOptions {
+                width: Width::W8,
+                ..wrapping
+            }
+        ),
+        Ok(Value::from(-56))
+    );
+}
+
+#[test]
+fn overflow_wrapping_unsigned() {
+    let wrapping = unsigned(Width::W8, OverflowPolicy::Wrapping);
+    assert_eq!(eval_with("1 - 2", &wrapping), Ok(Value::from(0xFFu8)));
+    assert_eq!(eval_with("0xFF + 2", &wrapping), Ok(Value::from(1u8)));
+    assert_eq!(
+        eval_with("0 - 1", &unsigned(Width::W128, OverflowPolicy::Wrapping)),
+        Ok(Value::from(u128::MAX))
+    );
+}
+
+#[test]
+fn overflow_wrapping_128_bit() {
+    let wrapping = EvalOptions {
+        width: Width::W128,
+        ..opts(OverflowPolicy::Wrapping)
+    };
+    assert_eq!(
+        eval_with("170141183460469231731687303715884105727 + 1", &wrapping),
+        Ok(Value::from(i128::MIN))
+    );
+}
+
+// saturating
+#[test]
+fn overflow_saturating_arithmetic() {
+    let saturating = opts(OverflowPolicy::Saturating);
+    assert_eq!(
+        eval_with("2147483647 + 1", &saturating),
+        Ok(Value::from(i32::MAX))
+    );
+    assert_eq!(
+        eval_with("-2147483648 - 1", &saturating),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("-2 * 2147483647", &saturating),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(eval_with("2**40", &saturating), Ok(Value::from(i32::MAX)));
+    assert_eq!(
+        eval_with("(-2)**33", &saturating),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("(-2)**31", &saturating),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("-2147483648 / -1", &saturating),
+        Ok(Value::from(i32::MAX))
+    );
+    assert_eq!(
+        eval_with("-(-2147483648)", &saturating),
+        Ok(Value::from(i32::MAX))
+    );
+}
+
+#[test]
+fn overflow_saturating_literals() {
+    let saturating = opts(OverflowPolicy::Saturating);
+    assert_eq!(
+        eval_with("0xFFFFFFFF", &saturating),
+        Ok(Value::from(i32::MAX))
+    );
+    assert_eq!(
+        eval_with("-0xFFFFFFFF", &saturating),
+        Ok(Value::from(i32::MIN))
+    );
+    assert_eq!(
+        eval_with("0xFFFFFFFF - 1", &saturating),
+        Ok(Value::from(i32::MAX - 1))
+    );
+}
+
+#[test]
+fn overflow_saturating_unsigned() {
+    let saturating = unsigned(Width::W8, OverflowPolicy::Saturating);
+    assert_eq!(eval_with("1 - 2", &saturating), Ok(Value::from(0u8)));
+    assert_eq!(eval_with("200 + 100", &saturating), Ok(Value::from(0xFFu8)));
+    assert_eq!(eval_with("-5", &saturating), Ok(Value::from(0u8)));
+    assert_eq!(eval_with("300", &saturating), Ok(Value::from(0xFFu8)));
+}
+
+// ポリシーに関係なくエラーになるもの
+#[test]
+fn overflow_policy_keeps_other_errors() {
+    for policy in [OverflowPolicy::Wrapping, OverflowPolicy::Saturating] {
+        let options = opts(policy);
+        assert_eq!(
+            eval_with("1 / 0", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(
+            eval_with("1 % 0", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::RemainderByZero)
+        );
+        assert_eq!(
+            eval_with("1 << 32", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::InvalidShiftAmount)
+        );
+        assert_eq!(
+            eval_with("3 << 31", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::ShiftOverflow)
+        );
+        assert_eq!(
+            eval_with("2 ** -1", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::NegativeExponent)
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use calc_core::{eval_expression, CalcError};
+use calc_core::{eval_expression, CalcError, ErrorKind};
 
 // 括弧の評価
 #[test]
@@ -25,20 +25,32 @@ fn parentheses_single_literal() {
 // 括弧の不正パターン
 #[test]
 fn parentheses_empty_error() {
-    assert_eq!(eval_expression("()"), Err(CalcError::InvalidToken(')')));
+    assert_eq!(
+        eval_expression("()").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
 }
 
 #[test]
 fn parentheses_unclosed_error() {
-    assert_eq!(eval_expression("(1+2"), Err(CalcError::InvalidToken(')')));
+    assert_eq!(
+        eval_expression("(1+2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
 }
 
 #[test]
 fn parentheses_extra_closing_error() {
-    assert_eq!(eval_expression("1+2)"), Err(CalcError::InvalidToken(')')));
+    assert_eq!(
+        eval_expression("1+2)").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
 }
 
 #[test]
 fn parentheses_inverted_error() {
-    assert_eq!(eval_expression(")("), Err(CalcError::InvalidToken(')')));
+    assert_eq!(
+        eval_expression(")(").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken(')'))
+    );
 }
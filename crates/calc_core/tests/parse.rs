@@ -1,4 +1,4 @@
-use calc_core::{parse, CalcError};
+use calc_core::{parse, CalcError, ErrorKind};
 
 #[test]
 fn parse_one() {
@@ -27,37 +27,37 @@ fn parse_binary_number1() {
 
 #[test]
 fn parse_invalid_decimal_underscore_leading() {
-    assert!(parse("_1") == Err(CalcError::InvalidLiteral));
+    assert!(parse("_1").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_decimal_underscore_double() {
-    assert!(parse("1__2") == Err(CalcError::InvalidLiteral));
+    assert!(parse("1__2").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_decimal_underscore_trailing() {
-    assert!(parse("1_") == Err(CalcError::InvalidLiteral));
+    assert!(parse("1_").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_binary_literal_empty() {
-    assert!(parse("0b") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0b").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_binary_literal_digit() {
-    assert!(parse("0b102") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0b102").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_binary_literal_trailing_underscore() {
-    assert!(parse("0b1_") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0b1_").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_token() {
-    assert!(parse("1a") == Err(CalcError::InvalidToken('a')));
+    assert!(parse("1a").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidToken('a')));
 }
 
 #[test]
@@ -72,26 +72,85 @@ fn parse_hex_with_underscore() {
 
 #[test]
 fn parse_invalid_hex_empty() {
-    assert!(parse("0x") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0x").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_hex_digit() {
-    assert!(parse("0x1G") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0x1G").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_invalid_hex_trailing_underscore() {
-    assert!(parse("0x1_") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0x1_").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
 }
 
 #[test]
 fn parse_hex_literal_range_error() {
-    assert!(parse("0x1_0000_0000") == Err(CalcError::LiteralOutOfRange));
+    assert!(
+        parse("0x1_0000_0000").map_err(CalcError::into_kind) == Err(ErrorKind::LiteralOutOfRange)
+    );
 }
 
 // 関係ない記号
 #[test]
 fn parse_hex_char_error() {
-    assert!(parse("0x0001_0-00") == Err(CalcError::InvalidLiteral));
+    assert!(parse("0x0001_0-00").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+}
+
+// 16 進リテラルの後の `-`: 直後なら不正なリテラル、空白を挟むと範囲外
+#[test]
+fn parse_hex_minus_error() {
+    assert!(parse("0x10-").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+    for input in ["0x10 -", "0x10 - 1", " 0x10  -x"] {
+        assert!(
+            parse(input).map_err(CalcError::into_kind) == Err(ErrorKind::LiteralOutOfRange),
+            "{input}"
+        );
+    }
+    assert!(parse("0b10 -").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidToken('-')));
+}
+
+#[test]
+fn parse_octal_number() {
+    assert!(parse("0o755") == Ok(493));
+}
+
+#[test]
+fn parse_octal_upper_prefix() {
+    assert!(parse("0O17") == Ok(15));
+}
+
+#[test]
+fn parse_octal_with_underscore() {
+    assert!(parse("0o7_777") == Ok(4095));
+}
+
+#[test]
+fn parse_invalid_octal_empty() {
+    assert!(parse("0o").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+}
+
+#[test]
+fn parse_invalid_octal_digit() {
+    assert!(parse("0o78").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+}
+
+#[test]
+fn parse_invalid_octal_underscore() {
+    assert!(parse("0o_7").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+    assert!(parse("0o7__7").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+    assert!(parse("0o7_").map_err(CalcError::into_kind) == Err(ErrorKind::InvalidLiteral));
+}
+
+#[test]
+fn parse_octal_literal_range_error() {
+    assert!(
+        parse("0o200_0000_0000").map_err(CalcError::into_kind) == Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+#[test]
+fn parse_octal_max() {
+    assert!(parse("0o177_7777_7777") == Ok(2147483647));
 }
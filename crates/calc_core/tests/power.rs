@@ -0,0 +1,137 @@
+mod common;
+
+use calc_core::{
+    eval_expression, eval_with, BinaryOp, CalcError, ErrorKind, EvalOptions, Precision, Signedness,
+    Value, Width,
+};
+use common::overflow;
+
+// べき乗
+#[test]
+fn power_basic() {
+    assert_eq!(eval_expression("2**10"), Ok(1024));
+    assert_eq!(eval_expression("2**20 - 1"), Ok(0xF_FFFF));
+}
+
+#[test]
+fn power_zero_exponent() {
+    assert_eq!(eval_expression("0**0"), Ok(1));
+    assert_eq!(eval_expression("7**0"), Ok(1));
+}
+
+#[test]
+fn power_negative_base_in_parentheses() {
+    assert_eq!(eval_expression("(-2)**3"), Ok(-8));
+    assert_eq!(eval_expression("(-2)**31"), Ok(-2147483648));
+}
+
+// 右結合
+#[test]
+fn power_right_associative() {
+    assert_eq!(eval_expression("2**3**2"), Ok(512));
+}
+
+// 単項マイナスより強く結合する
+#[test]
+fn power_binds_tighter_than_unary_minus() {
+    assert_eq!(eval_expression("-2**2"), Ok(-4));
+    assert_eq!(eval_expression("~2**2"), Ok(-5));
+}
+
+#[test]
+fn power_exponent_may_have_unary_operator() {
+    assert_eq!(eval_expression("2**-0"), Ok(1));
+    assert_eq!(eval_expression("2**~-4"), Ok(8));
+}
+
+#[test]
+fn power_precedence_over_multiplication() {
+    assert_eq!(eval_expression("3*2**4"), Ok(48));
+    assert_eq!(eval_expression("2**4*3"), Ok(48));
+}
+
+// 範囲
+#[test]
+fn power_range_error() {
+    assert_eq!(
+        eval_expression("2**31").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[2, 31]))
+    );
+    assert_eq!(
+        eval_expression("10**100").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[10, 100]))
+    );
+    assert_eq!(
+        eval_expression("-2**31").map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[2, 31]))
+    );
+}
+
+#[test]
+fn power_large_exponent_with_unit_base() {
+    assert_eq!(eval_expression("1**2147483647"), Ok(1));
+    assert_eq!(eval_expression("(-1)**2147483647"), Ok(-1));
+}
+
+// 指数が u32 に収まらなくても底が 0, 1, -1 なら値が決まる
+#[test]
+fn power_exponent_beyond_u32() {
+    let wide = EvalOptions {
+        width: Width::W64,
+        ..EvalOptions::default()
+    };
+    let unsigned = EvalOptions {
+        signedness: Signedness::Unsigned,
+        ..wide
+    };
+    let arbitrary = EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    };
+    for options in [wide, unsigned, arbitrary] {
+        assert_eq!(eval_with("1 ** 4294967296", &options), Ok(Value::from(1)));
+        assert_eq!(eval_with("0 ** 4294967297", &options), Ok(Value::from(0)));
+        assert_eq!(
+            eval_with("2 ** 4294967296", &options).map_err(CalcError::into_kind),
+            Err(ErrorKind::Overflow {
+                op: BinaryOp::Pow.into(),
+                operands: vec![Value::from(2), Value::from(4294967296_i64)],
+            })
+        );
+    }
+    for options in [wide, arbitrary] {
+        assert_eq!(
+            eval_with("(-1) ** 4294967296", &options),
+            Ok(Value::from(1))
+        );
+        assert_eq!(
+            eval_with("(-1) ** 4294967297", &options),
+            Ok(Value::from(-1))
+        );
+    }
+}
+
+#[test]
+fn power_negative_exponent_error() {
+    assert_eq!(
+        eval_expression("2**-1").map_err(CalcError::into_kind),
+        Err(ErrorKind::NegativeExponent)
+    );
+}
+
+#[test]
+fn power_base_literal_range_error() {
+    assert_eq!(
+        eval_expression("-2147483648**1").map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+// 演算子の異常系
+#[test]
+fn trailing_power_operator_error() {
+    assert_eq!(
+        eval_expression("2**").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('*'))
+    );
+}
@@ -0,0 +1,118 @@
+use calc_core::{
+    eval_with, pretty_print, CalcError, EvalOptions, OverflowPolicy, PrettyOptions, Signedness,
+    Width,
+};
+
+fn pretty(input: &str) -> String {
+    pretty_print(input, &PrettyOptions::default()).unwrap()
+}
+
+// 空白と括弧
+#[test]
+fn pretty_spacing() {
+    assert_eq!(pretty("1+2*3"), "1 + 2 * 3");
+    assert_eq!(pretty("  -  x<<2|~y "), "-x << 2 | ~y");
+    assert_eq!(pretty("max( 1 ,min(2,3) )"), "max(1, min(2, 3))");
+    assert_eq!(pretty("x rotl 3>>>1"), "x rotl 3 >>> 1");
+}
+
+#[test]
+fn pretty_minimal_parens() {
+    assert_eq!(pretty("((1)) + (2 * 3)"), "1 + 2 * 3");
+    assert_eq!(pretty("(1 + 2) * 3"), "(1 + 2) * 3");
+    assert_eq!(pretty("(1 - 2) - 3"), "1 - 2 - 3");
+    assert_eq!(pretty("1 - (2 - 3)"), "1 - (2 - 3)");
+    assert_eq!(pretty("1 + (2 + 3)"), "1 + (2 + 3)");
+    assert_eq!(pretty("(1 | 2) & 3"), "(1 | 2) & 3");
+    assert_eq!(pretty("max((1 + 2), (3))"), "max(1 + 2, 3)");
+}
+
+#[test]
+fn pretty_power_and_unary() {
+    assert_eq!(pretty("2 ** (3 ** 2)"), "2 ** 3 ** 2");
+    assert_eq!(pretty("(2 ** 3) ** 2"), "(2 ** 3) ** 2");
+    assert_eq!(pretty("-(2 ** 2)"), "-2 ** 2");
+    assert_eq!(pretty("(-2) ** 2"), "(-2) ** 2");
+    assert_eq!(pretty("~(x ** 2)"), "~x ** 2");
+    assert_eq!(pretty("-(-x)"), "--x");
+    assert_eq!(pretty("-(x + 1)"), "-(x + 1)");
+    // -(リテラル) は -リテラル と評価が違うので括弧を残す
+    assert_eq!(pretty("-((0xFF))"), "-(0xFF)");
+    assert_eq!(pretty("~(5)"), "~5");
+}
+
+// リテラルの表記
+#[test]
+fn pretty_literal_case() {
+    assert_eq!(pretty("0XfF + 0B1010 + 0O17"), "0xFF + 0b1010 + 0o17");
+    assert_eq!(pretty("36Rzz"), "36rZZ");
+    assert_eq!(pretty("'a' + b\"ab\""), "'a' + b\"ab\"");
+    let lower = PrettyOptions {
+        uppercase: false,
+        ..PrettyOptions::default()
+    };
+    assert_eq!(pretty_print("0xABCD", &lower), Ok("0xabcd".to_string()));
+}
+
+#[test]
+fn pretty_digit_grouping() {
+    assert_eq!(pretty("0xDEAD_BEEF + 1_000"), "0xDEADBEEF + 1000");
+    let grouped = PrettyOptions {
+        group: Some(4),
+        ..PrettyOptions::default()
+    };
+    assert_eq!(
+        pretty_print("0xdeadbeef + 0b1_0100_1 + 123", &grouped),
+        Ok("0xDEAD_BEEF + 0b10_1001 + 123".to_string())
+    );
+}
+
+#[test]
+fn pretty_parse_errors() {
+    assert!(pretty_print("1 +", &PrettyOptions::default()).is_err());
+}
+
+// 整形前後で評価結果が変わらない
+#[test]
+fn pretty_preserves_value() {
+    let inputs = [
+        "1 - (2 - 3) * 4",
+        "-(2 ** 2) + (-2) ** 2",
+        "-(0x7F) - (0x7F)",
+        "-((0xFF))",
+        "-0xFF",
+        "~(5) ^ -(-3)",
+        "(0x80 >> 2) | 0b1 << 3",
+        "((100 + 100) + 100) / 3 % 7",
+        "max(-(1), abs(-128)) ** (1 + 1)",
+    ];
+    let mut options = Vec::new();
+    for width in [Width::W8, Width::W32] {
+        for overflow in [
+            OverflowPolicy::Checked,
+            OverflowPolicy::Wrapping,
+            OverflowPolicy::Saturating,
+        ] {
+            for signedness in [Signedness::Signed, Signedness::Unsigned] {
+                options.push(EvalOptions {
+                    width,
+                    overflow,
+                    signedness,
+                    ..EvalOptions::default()
+                });
+            }
+        }
+    }
+
+    for input in inputs {
+        let printed = pretty(input);
+        assert_eq!(pretty(&printed), printed);
+        for options in &options {
+            assert_eq!(
+                eval_with(&printed, options).map_err(CalcError::into_kind),
+                eval_with(input, options).map_err(CalcError::into_kind),
+                "{input} => {printed} with {options:?}"
+            );
+        }
+    }
+}
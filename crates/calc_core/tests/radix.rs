@@ -0,0 +1,160 @@
+use calc_core::{eval_expression, format_radix, parse, CalcError, ErrorKind, FormatOptions, Value};
+
+// 任意基数リテラル
+#[test]
+fn radix_literal_base36() {
+    assert_eq!(parse("36rZZ"), Ok(1295));
+    assert_eq!(parse("36rzz"), Ok(1295));
+}
+
+#[test]
+fn radix_literal_small_bases() {
+    assert_eq!(parse("3r1012"), Ok(32));
+    assert_eq!(parse("5r4_4"), Ok(24));
+}
+
+#[test]
+fn radix_literal_matches_prefixed_literals() {
+    assert_eq!(parse("2r1010"), parse("0b1010"));
+    assert_eq!(parse("8r755"), parse("0o755"));
+    assert_eq!(parse("16rFF"), parse("0xFF"));
+    assert_eq!(parse("10r123"), parse("123"));
+}
+
+#[test]
+fn radix_literal_upper_r() {
+    assert_eq!(parse("16RFF"), Ok(255));
+}
+
+#[test]
+fn radix_literal_in_expression() {
+    assert_eq!(eval_expression("36rZZ + 1"), Ok(1296));
+    assert_eq!(eval_expression("-3r2"), Ok(-2));
+}
+
+#[test]
+fn radix_literal_invalid_radix_error() {
+    assert_eq!(
+        parse("1r0").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidRadix)
+    );
+    assert_eq!(
+        parse("37r1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidRadix)
+    );
+    assert_eq!(
+        parse("0r1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidRadix)
+    );
+}
+
+#[test]
+fn radix_literal_invalid_digit_error() {
+    assert_eq!(
+        parse("3r3").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("16rG").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+}
+
+#[test]
+fn radix_literal_underscore_rules() {
+    assert_eq!(
+        parse("36r").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("36r_Z").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("36rZ_").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("36rZ__Z").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+    assert_eq!(
+        parse("3_6rZ").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+}
+
+#[test]
+fn radix_literal_range_error() {
+    assert_eq!(parse("36rZIK0ZJ"), Ok(2147483647));
+    assert_eq!(
+        parse("36rZIK0ZK").map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+// 任意基数の出力
+#[test]
+fn format_radix_default_options() {
+    let options = FormatOptions::default();
+    assert_eq!(format_radix(1295, 36, &options), Ok("36rZZ".to_string()));
+    assert_eq!(format_radix(255, 16, &options), Ok("0xFF".to_string()));
+    assert_eq!(format_radix(-24, 5, &options), Ok("-5r44".to_string()));
+    assert_eq!(format_radix(0, 3, &options), Ok("3r0".to_string()));
+}
+
+#[test]
+fn format_radix_without_prefix_lowercase() {
+    let options = FormatOptions {
+        prefix: false,
+        uppercase: false,
+        ..FormatOptions::default()
+    };
+    assert_eq!(format_radix(1295, 36, &options), Ok("zz".to_string()));
+}
+
+#[test]
+fn format_radix_grouped() {
+    let options = FormatOptions {
+        group: Some(3),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_radix(1_234_567, 10, &options),
+        Ok("1_234_567".to_string())
+    );
+    assert_eq!(format_radix(0o7777, 8, &options), Ok("0o7_777".to_string()));
+}
+
+#[test]
+fn format_radix_round_trip() {
+    let options = FormatOptions::default();
+    for radix in 2..=36 {
+        for value in [0, 1, 35, 1295, 2147483647] {
+            let text = format_radix(value, radix, &options).unwrap();
+            assert_eq!(parse(&text), Ok(value), "radix={radix} text={text}");
+        }
+    }
+}
+
+#[test]
+fn format_radix_invalid_radix_error() {
+    let options = FormatOptions::default();
+    assert_eq!(
+        format_radix(1, 1, &options).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidRadix)
+    );
+    assert_eq!(
+        format_radix(1, 37, &options).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidRadix)
+    );
+}
+
+#[test]
+fn format_radix_out_of_range_error() {
+    let options = FormatOptions::default();
+    assert_eq!(
+        format_radix(2147483648, 16, &options).map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(2147483648i64)))
+    );
+}
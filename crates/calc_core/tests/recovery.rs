@@ -0,0 +1,121 @@
+use calc_core::{parse_ast, parse_ast_recovering, ErrorKind, PartialAst, Span};
+
+fn diagnostics(input: &str) -> Vec<(ErrorKind, Span)> {
+    parse_ast_recovering(input)
+        .diagnostics
+        .into_iter()
+        .map(|err| (err.kind().clone(), err.span().unwrap()))
+        .collect()
+}
+
+fn partial(input: &str) -> Vec<&str> {
+    parse_ast_recovering(input)
+        .exprs
+        .iter()
+        .map(|expr| &input[expr.span().start..expr.span().end])
+        .collect()
+}
+
+// 正しい式は parse_ast と同じ結果
+#[test]
+fn recovering_valid_input() {
+    for input in ["1 + 2 * 3", "-(~x) ** 2", "max(0xFF, 'A') << 2"] {
+        assert_eq!(
+            parse_ast_recovering(input),
+            PartialAst {
+                exprs: vec![parse_ast(input).unwrap()],
+                diagnostics: Vec::new(),
+            }
+        );
+    }
+}
+
+#[test]
+fn recovering_first_diagnostic_matches_parse_ast() {
+    for input in ["1 +", "(1", "1 # 2", "0x1G", "foo(1)", "x = 1", ""] {
+        let err = parse_ast(input).unwrap_err();
+        assert_eq!(parse_ast_recovering(input).diagnostics.first(), Some(&err));
+    }
+}
+
+// 複数のエラーをまとめて報告する
+#[test]
+fn recovering_reports_every_problem() {
+    assert_eq!(
+        diagnostics("1 +* 2 ** 3 %% 4 + 0x1G"),
+        vec![
+            (ErrorKind::InvalidToken('*'), Span::new(3, 4)),
+            (ErrorKind::InvalidToken('%'), Span::new(13, 14)),
+            (ErrorKind::InvalidLiteral, Span::new(19, 23)),
+        ]
+    );
+    assert_eq!(
+        diagnostics("foo(1 +, 2) # 3"),
+        vec![
+            (ErrorKind::UnknownFunction("foo".to_string()), Span::new(0, 3)),
+            (ErrorKind::InvalidToken(','), Span::new(7, 8)),
+            (ErrorKind::InvalidToken('#'), Span::new(12, 13)),
+        ]
+    );
+}
+
+// 壊れた部分を除いた構文木
+#[test]
+fn recovering_bad_tokens() {
+    assert_eq!(partial("1 + + 2"), vec!["1 + + 2"]);
+    assert_eq!(
+        diagnostics("1 + + 2"),
+        vec![(ErrorKind::InvalidToken('+'), Span::new(4, 5))]
+    );
+    assert_eq!(partial("1 + * -2"), vec!["1 + * -2"]);
+    assert_eq!(partial("0x1G + 1"), vec!["1"]);
+}
+
+#[test]
+fn recovering_unbalanced_parens() {
+    assert_eq!(partial("(1 + 2"), vec!["(1 + 2"]);
+    assert_eq!(
+        diagnostics("(1 + 2"),
+        vec![(ErrorKind::InvalidToken(')'), Span::new(6, 6))]
+    );
+    assert_eq!(partial("1 + 2) * 3"), vec!["1 + 2", "3"]);
+    assert_eq!(
+        diagnostics("1 + 2) * 3"),
+        vec![(ErrorKind::InvalidToken(')'), Span::new(5, 6))]
+    );
+    assert_eq!(partial("()"), Vec::<&str>::new());
+    assert_eq!(
+        diagnostics("()"),
+        vec![(ErrorKind::InvalidToken(')'), Span::new(1, 2))]
+    );
+}
+
+#[test]
+fn recovering_dangling_operators() {
+    assert_eq!(partial("1 +"), vec!["1"]);
+    assert_eq!(partial("(1 + ) * 3"), vec!["(1 + ) * 3"]);
+    assert_eq!(
+        diagnostics("(1 + ) * 3"),
+        vec![(ErrorKind::InvalidToken(')'), Span::new(5, 6))]
+    );
+    assert_eq!(partial("2 **"), vec!["2"]);
+    assert_eq!(partial("max(, 1)"), vec!["max(, 1)"]);
+}
+
+// 読み飛ばしたトークンで区切られた断片はすべて返す
+#[test]
+fn recovering_keeps_every_fragment() {
+    assert_eq!(partial("1 $ 2 # 3"), vec!["1", "2", "3"]);
+    assert_eq!(
+        diagnostics("1 $ 2 # 3"),
+        vec![
+            (ErrorKind::InvalidToken('$'), Span::new(2, 3)),
+            (ErrorKind::InvalidToken('#'), Span::new(6, 7)),
+        ]
+    );
+    assert_eq!(partial("0x1G + 1"), vec!["1"]);
+    assert_eq!(
+        partial("max(1, 2)) + 3 ) * -4"),
+        vec!["max(1, 2)", "3", "-4"]
+    );
+}
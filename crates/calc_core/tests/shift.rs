@@ -0,0 +1,124 @@
+use calc_core::{eval_expression, CalcError, ErrorKind};
+
+// 左シフト
+#[test]
+fn shift_left() {
+    assert_eq!(eval_expression("1 << 12"), Ok(4096));
+}
+
+#[test]
+fn shift_left_negative_value() {
+    assert_eq!(eval_expression("-1 << 31"), Ok(-2147483648));
+}
+
+#[test]
+fn shift_left_overflow_error() {
+    assert_eq!(
+        eval_expression("1 << 31").map_err(CalcError::into_kind),
+        Err(ErrorKind::ShiftOverflow)
+    );
+    assert_eq!(
+        eval_expression("0x4000_0000 << 1").map_err(CalcError::into_kind),
+        Err(ErrorKind::ShiftOverflow)
+    );
+}
+
+// 算術右シフト
+#[test]
+fn shift_right_arithmetic() {
+    assert_eq!(eval_expression("0xF0 >> 4"), Ok(0xF));
+    assert_eq!(eval_expression("-16 >> 2"), Ok(-4));
+    assert_eq!(eval_expression("-1 >> 31"), Ok(-1));
+}
+
+// 論理右シフト
+#[test]
+fn shift_right_logical() {
+    assert_eq!(eval_expression("-1 >>> 28"), Ok(0xF));
+    assert_eq!(eval_expression("0xF0 >>> 4"), Ok(0xF));
+    assert_eq!(eval_expression("-1 >>> 0"), Ok(-1));
+}
+
+// ローテート
+#[test]
+fn rotate_left() {
+    assert_eq!(eval_expression("0x1234_5678 rotl 8"), Ok(0x3456_7812));
+    assert_eq!(eval_expression("0x4000_0000 rotl 2"), Ok(1));
+}
+
+#[test]
+fn rotate_right() {
+    assert_eq!(eval_expression("0x1234_5678 rotr 8"), Ok(0x7812_3456));
+    assert_eq!(eval_expression("1 rotr 1"), Ok(-2147483648));
+}
+
+// シフト量の範囲
+#[test]
+fn shift_amount_out_of_range_error() {
+    assert_eq!(
+        eval_expression("1 << 32").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+    assert_eq!(
+        eval_expression("1 >> -1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+    assert_eq!(
+        eval_expression("1 >>> 32").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+    assert_eq!(
+        eval_expression("1 rotl 32").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+}
+
+// 優先順位（+ - より低く & より高い）
+#[test]
+fn shift_precedence() {
+    assert_eq!(eval_expression("1 << 2 + 1"), Ok(8));
+    assert_eq!(eval_expression("0xFF << 8 | 0b1010"), Ok(0xFF0A));
+    assert_eq!(eval_expression("0xF0 >> 4 & 3"), Ok(3));
+}
+
+#[test]
+fn shift_left_associative() {
+    assert_eq!(eval_expression("256 >> 2 >> 2"), Ok(16));
+}
+
+// 演算子の異常系
+#[test]
+fn trailing_shift_operator_error() {
+    assert_eq!(
+        eval_expression("1 <<").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('<'))
+    );
+    assert_eq!(
+        eval_expression("1 >>>").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('>'))
+    );
+    assert_eq!(
+        eval_expression("1 rotl").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('r'))
+    );
+}
+
+#[test]
+fn single_angle_bracket_error() {
+    assert_eq!(
+        eval_expression("1 < 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('<'))
+    );
+    assert_eq!(
+        eval_expression("1 >>>> 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('>'))
+    );
+}
+
+#[test]
+fn rotate_keyword_requires_word_boundary() {
+    assert_eq!(
+        eval_expression("1 rotlx 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('r'))
+    );
+}
@@ -0,0 +1,135 @@
+use calc_core::{
+    eval_expression, parse, tokenize, CalcError, ErrorKind, LiteralBase, Operator, Span, TokenKind,
+};
+
+fn kinds(input: &str) -> Vec<TokenKind> {
+    tokenize(input)
+        .into_iter()
+        .map(|token| token.kind)
+        .collect()
+}
+
+fn texts(input: &str) -> Vec<&str> {
+    tokenize(input)
+        .into_iter()
+        .map(|token| token.text(input))
+        .collect()
+}
+
+// 種類とバイト位置
+#[test]
+fn tokenize_kinds_and_spans() {
+    let tokens = tokenize("0xFF << 8");
+    assert_eq!(tokens.len(), 5);
+    assert_eq!(tokens[0].kind, TokenKind::Literal(LiteralBase::Hex));
+    assert_eq!(tokens[0].span, Span::new(0, 4));
+    assert_eq!(tokens[1].kind, TokenKind::Whitespace);
+    assert_eq!(tokens[2].kind, TokenKind::Operator(Operator::Shl));
+    assert_eq!(tokens[2].span, Span::new(5, 7));
+    assert_eq!(tokens[4].kind, TokenKind::Literal(LiteralBase::Decimal));
+    assert_eq!(tokens[4].span, Span::new(8, 9));
+}
+
+#[test]
+fn tokenize_literal_bases() {
+    assert_eq!(
+        kinds("0b1 0o7 42 0xF 36rZZ 'A' b\"AB\"le"),
+        vec![
+            TokenKind::Literal(LiteralBase::Binary),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Octal),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Decimal),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Hex),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Radix(36)),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Char),
+            TokenKind::Whitespace,
+            TokenKind::Literal(LiteralBase::Bytes),
+        ]
+    );
+}
+
+#[test]
+fn tokenize_operators_longest_match() {
+    assert_eq!(
+        texts("1>>>2>>3**4*5"),
+        vec!["1", ">>>", "2", ">>", "3", "**", "4", "*", "5"]
+    );
+    assert_eq!(
+        kinds("x rotl rotlx"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::Operator(Operator::Rotl),
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+        ]
+    );
+}
+
+#[test]
+fn tokenize_parens_and_calls() {
+    assert_eq!(
+        kinds("min(1,(2))"),
+        vec![
+            TokenKind::Identifier,
+            TokenKind::OpenParen,
+            TokenKind::Literal(LiteralBase::Decimal),
+            TokenKind::Comma,
+            TokenKind::OpenParen,
+            TokenKind::Literal(LiteralBase::Decimal),
+            TokenKind::CloseParen,
+            TokenKind::CloseParen,
+        ]
+    );
+}
+
+#[test]
+fn tokenize_whitespace_runs() {
+    assert_eq!(texts(" \t1 \n"), vec![" \t", "1", " \n"]);
+}
+
+// 不正な入力もトークン化はできる
+#[test]
+fn tokenize_invalid_input() {
+    assert_eq!(texts("0x1G"), vec!["0x1G"]);
+    assert_eq!(texts("'A"), vec!["'A"]);
+    assert_eq!(texts("1 # 2"), vec!["1", " ", "#", " ", "2"]);
+    assert_eq!(kinds("#")[0], TokenKind::Unknown);
+    assert_eq!(kinds("é"), vec![TokenKind::Unknown]);
+    assert_eq!(tokenize("é")[0].span, Span::new(0, 2));
+}
+
+#[test]
+fn tokenize_empty() {
+    assert!(tokenize("").is_empty());
+}
+
+// 評価とトークンの区切りが一致する
+#[test]
+fn tokenize_matches_evaluator() {
+    assert_eq!(eval_expression("0x10 - 1"), Ok(15));
+    assert_eq!(eval_expression("0b10-1"), Ok(1));
+    assert_eq!(eval_expression("'A'+1"), Ok(66));
+    assert_eq!(
+        eval_expression("1 # 2").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('#'))
+    );
+    assert_eq!(
+        eval_expression("0x1G + 1").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidLiteral)
+    );
+}
+
+// 16 進リテラルに空白なしで続く `-` は parse と同じく不正なリテラル
+#[test]
+fn hex_literal_followed_by_minus() {
+    for input in ["0x10-1", "0xFF-1"] {
+        let expected = Err(CalcError::new(ErrorKind::InvalidLiteral, Span::new(0, 5)));
+        assert_eq!(eval_expression(input), expected);
+        assert_eq!(parse(input), expected);
+    }
+}
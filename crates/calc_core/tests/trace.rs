@@ -0,0 +1,171 @@
+use calc_core::{
+    eval_in, eval_in_traced, eval_traced, eval_traced_in, format_all, BinaryOp, CalcError, Context,
+    ErrorKind, EvalOptions, Operation, OverflowPolicy, Precision, Span, UnaryOp, Value, Width,
+    MAX_TRACE_STEPS,
+};
+
+fn steps(input: &str) -> Vec<(String, Vec<String>, Value)> {
+    eval_traced(input, &EvalOptions::default())
+        .unwrap()
+        .into_iter()
+        .map(|step| (step.text(input).to_string(), step.operands, step.value))
+        .collect()
+}
+
+fn step(expr: &str, operands: &[&str], value: i64) -> (String, Vec<String>, Value) {
+    (
+        expr.to_string(),
+        operands.iter().map(|operand| operand.to_string()).collect(),
+        Value::from(value),
+    )
+}
+
+// 簡約の順序
+#[test]
+fn trace_reduction_order() {
+    assert_eq!(
+        steps("(0xF0 | 0x0F) & 0b1010"),
+        vec![
+            step("0xF0 | 0x0F", &["0xF0", "0x0F"], 0xFF),
+            step("(0xF0 | 0x0F) & 0b1010", &["0xFF", "0b1010"], 0b1010),
+        ]
+    );
+    assert_eq!(
+        steps("1 + 2 * 3"),
+        vec![
+            step("2 * 3", &["2", "3"], 6),
+            step("1 + 2 * 3", &["1", "6"], 7),
+        ]
+    );
+}
+
+#[test]
+fn trace_literals_have_no_steps() {
+    assert_eq!(steps("0x10"), Vec::new());
+    assert_eq!(steps("-0x10"), Vec::new());
+    assert_eq!(steps("((42))"), Vec::new());
+}
+
+// 被演算子は元の基数で表示する
+#[test]
+fn trace_operands_keep_bases() {
+    assert_eq!(
+        steps("~(0b1100 << 2) + -0x10"),
+        vec![
+            step("0b1100 << 2", &["0b1100", "2"], 48),
+            step("~(0b1100 << 2)", &["0b110000"], -49),
+            step("~(0b1100 << 2) + -0x10", &["-0b110001", "-0x10"], -65),
+        ]
+    );
+    assert_eq!(
+        steps("max(8r17, 'A')"),
+        vec![step("max(8r17, 'A')", &["8r17", "'A'"], 65)]
+    );
+}
+
+#[test]
+fn trace_step_details() {
+    let trace = eval_traced("-(2 ** 3)", &EvalOptions::default()).unwrap();
+    assert_eq!(trace.len(), 2);
+    assert_eq!(trace[0].op, Operation::Binary(BinaryOp::Pow));
+    assert_eq!(trace[0].span, Span::new(2, 8));
+    assert_eq!(trace[1].op, Operation::Unary(UnaryOp::Neg));
+    assert_eq!(trace[1].result, format_all(-8).unwrap());
+}
+
+#[test]
+fn trace_uses_context() {
+    let mut ctx = Context::new();
+    ctx.set("mask", 0xFF);
+    let trace = eval_traced_in(&ctx, "y = mask & 0x0F").unwrap();
+    assert_eq!(trace.len(), 1);
+    assert_eq!(trace[0].operands, vec!["255", "0x0F"]);
+    assert_eq!(trace[0].value, Value::from(0x0F));
+    assert_eq!(ctx.get("y"), None);
+}
+
+#[test]
+fn trace_errors() {
+    let w8 = EvalOptions {
+        width: Width::W8,
+        ..EvalOptions::default()
+    };
+    assert_eq!(
+        eval_traced("-128 + 0", &w8).unwrap()[0].operands,
+        vec!["-128", "0"]
+    );
+    assert_eq!(
+        eval_traced("1 + (100 + 100)", &w8).map_err(CalcError::into_kind),
+        Err(ErrorKind::Overflow {
+            op: Operation::Binary(BinaryOp::Add),
+            operands: vec![Value::from(100), Value::from(100)],
+        })
+    );
+    assert_eq!(
+        eval_traced("1 +", &w8).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('+'))
+    );
+}
+
+// 過程の値は表示した被演算子から求め、eval_in と同じ結果・エラーになる
+#[test]
+fn trace_matches_eval_in() {
+    let wrapping = Context::with_options(EvalOptions {
+        width: Width::W8,
+        overflow: OverflowPolicy::Wrapping,
+        ..EvalOptions::default()
+    });
+    let arbitrary = Context::with_options(EvalOptions {
+        precision: Precision::Arbitrary,
+        ..EvalOptions::default()
+    });
+    let inputs = [
+        "(0x7F + 1) * 3 - -0x80",
+        "~x ** 2 % 7",
+        "max(x, 200) / 0",
+        "1 << 200",
+    ];
+    for base in [wrapping, arbitrary] {
+        for input in inputs {
+            let mut context = base.clone();
+            context.set("x", 5);
+            let expected = eval_in(&mut context.clone(), input);
+            let traced = eval_in_traced(&mut context, input);
+            assert_eq!(traced.as_ref().map(|(value, _)| value), expected.as_ref());
+            if let Ok((value, steps)) = traced {
+                assert_eq!(steps.last().map(|step| &step.value), Some(&value));
+                assert_eq!(context.get("ans"), Some(value));
+            }
+        }
+    }
+}
+
+// 代入は eval_in と同じく文脈に反映する
+#[test]
+fn trace_in_assigns() {
+    let mut ctx = Context::new();
+    let (value, steps) = eval_in_traced(&mut ctx, "y = 0xF0 | 0x0F").unwrap();
+    assert_eq!(value, Value::from(0xFF));
+    assert_eq!(steps.len(), 1);
+    assert_eq!(ctx.get("y"), Some(Value::from(0xFF)));
+    assert_eq!(
+        eval_in_traced(&mut ctx, "y +").map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidToken('+'))
+    );
+}
+
+// 記録するステップ数には上限があり、値は最後まで求める
+#[test]
+fn trace_step_limit() {
+    let options = EvalOptions {
+        max_tokens: 10_000,
+        ..EvalOptions::default()
+    };
+    let input = format!("0{}", " + 1".repeat(1500));
+    let mut ctx = Context::with_options(options);
+    let (value, steps) = eval_in_traced(&mut ctx, &input).unwrap();
+    assert_eq!(value, Value::from(1500));
+    assert_eq!(steps.len(), MAX_TRACE_STEPS);
+    assert_eq!(steps[0].text(&input), "0 + 1");
+    assert_eq!(steps.last().unwrap().value, Value::from(1000));
+}
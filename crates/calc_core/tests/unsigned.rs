@@ -0,0 +1,200 @@
+mod common;
+
+use calc_core::{
+    eval_in, eval_with, format_all_with, format_radix_with, BinaryOp, CalcError, Context,
+    ErrorKind, EvalOptions, FormatOptions, FormattedValue, Signedness, UnaryOp, Value, Width,
+};
+use common::overflow;
+
+fn unsigned(width: Width) -> EvalOptions {
+    EvalOptions {
+        width,
+        signedness: Signedness::Unsigned,
+        ..EvalOptions::default()
+    }
+}
+
+// 既定は符号付き
+#[test]
+fn unsigned_default_is_signed() {
+    assert_eq!(EvalOptions::default().signedness, Signedness::Signed);
+    assert_eq!(
+        eval_with("0xFFFFFFFF", &EvalOptions::default()).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+// リテラルの範囲
+#[test]
+fn unsigned_literal_limits() {
+    let opts = unsigned(Width::W32);
+    assert_eq!(eval_with("0xFFFFFFFF", &opts), Ok(Value::from(u32::MAX)));
+    assert_eq!(
+        eval_with("0x1_0000_0000", &opts).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        eval_with("255", &unsigned(Width::W8)),
+        Ok(Value::from(255u8))
+    );
+    assert_eq!(
+        eval_with("256", &unsigned(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        eval_with(
+            "0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF",
+            &unsigned(Width::W128)
+        ),
+        Ok(Value::from(u128::MAX))
+    );
+}
+
+// 減算のアンダーフロー
+#[test]
+fn unsigned_subtraction_underflow() {
+    let opts = unsigned(Width::W32);
+    assert_eq!(
+        eval_with("1 - 2", &opts).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Sub, &[1, 2]))
+    );
+    assert_eq!(eval_with("2 - 2", &opts), Ok(Value::from(0u32)));
+    assert_eq!(
+        eval_with("-5", &opts).map_err(CalcError::into_kind),
+        Err(overflow(UnaryOp::Neg, &[5]))
+    );
+    assert_eq!(eval_with("-0", &opts), Ok(Value::from(0u32)));
+    assert_eq!(
+        eval_with("-(1)", &opts).map_err(CalcError::into_kind),
+        Err(overflow(UnaryOp::Neg, &[1]))
+    );
+}
+
+#[test]
+fn unsigned_arithmetic_overflow() {
+    let opts = unsigned(Width::W32);
+    assert_eq!(
+        eval_with("0xFFFFFFFF + 1", &opts).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[0xFFFFFFFF, 1]))
+    );
+    assert_eq!(
+        eval_with("0x10000 * 0x10000", &opts).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Mul, &[0x10000, 0x10000]))
+    );
+    assert_eq!(
+        eval_with("0xFFFFFFFF / 16", &opts),
+        Ok(Value::from(0x0FFF_FFFFu32))
+    );
+    assert_eq!(
+        eval_with("7 % 0", &opts).map_err(CalcError::into_kind),
+        Err(ErrorKind::RemainderByZero)
+    );
+}
+
+// ビット演算
+#[test]
+fn unsigned_bitwise() {
+    let opts = unsigned(Width::W32);
+    assert_eq!(eval_with("~0", &opts), Ok(Value::from(u32::MAX)));
+    assert_eq!(
+        eval_with("~0", &unsigned(Width::W8)),
+        Ok(Value::from(0xFFu8))
+    );
+    assert_eq!(
+        eval_with("0xF0F0F0F0 | 0x0F0F0F0F", &opts),
+        Ok(Value::from(u32::MAX))
+    );
+    assert_eq!(eval_with("0x80000000 >> 31", &opts), Ok(Value::from(1u32)));
+    assert_eq!(eval_with("1 << 31", &opts), Ok(Value::from(0x8000_0000u32)));
+    assert_eq!(
+        eval_with("1 << 32", &opts).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+    assert_eq!(
+        eval_with("3 << 31", &opts).map_err(CalcError::into_kind),
+        Err(ErrorKind::ShiftOverflow)
+    );
+    assert_eq!(
+        eval_with("1 rotr 1", &opts),
+        Ok(Value::from(0x8000_0000u32))
+    );
+}
+
+#[test]
+fn unsigned_builtins() {
+    let opts = unsigned(Width::W32);
+    assert_eq!(
+        eval_with("bswap(0x12345678)", &opts),
+        Ok(Value::from(0x7856_3412u32))
+    );
+    assert_eq!(
+        eval_with("bitrev(1)", &opts),
+        Ok(Value::from(0x8000_0000u32))
+    );
+    assert_eq!(eval_with("abs(5)", &opts), Ok(Value::from(5u32)));
+    assert_eq!(
+        eval_with("max(0xFFFFFFFF, 1)", &opts),
+        Ok(Value::from(u32::MAX))
+    );
+}
+
+// Context
+#[test]
+fn unsigned_context_variables() {
+    let mut ctx = Context::with_options(unsigned(Width::W32));
+    eval_in(&mut ctx, "mask = 0xFFFF0000").unwrap();
+    assert_eq!(ctx.get("mask"), Some(Value::from(0xFFFF_0000u32)));
+    assert_eq!(eval_in(&mut ctx, "ans >> 16"), Ok(Value::from(0xFFFFu32)));
+
+    ctx.set("neg", -1);
+    assert_eq!(
+        eval_in(&mut ctx, "neg").map_err(CalcError::into_kind),
+        Err(ErrorKind::VariableOutOfRange("neg".to_string()))
+    );
+}
+
+#[test]
+fn unsigned_value_equality_across_kinds() {
+    assert_eq!(Value::from(5u32), Value::from(5i64));
+    assert_ne!(Value::from(-1), Value::from(u128::MAX));
+    assert_eq!(Value::from(-1).as_u128(), None);
+    assert_eq!(Value::from(u128::MAX).as_i128(), None);
+}
+
+// 出力
+#[test]
+fn unsigned_format_all() {
+    assert_eq!(
+        format_all_with(u32::MAX, &unsigned(Width::W32)),
+        Ok(FormattedValue {
+            bin: "0b1111_1111_1111_1111_1111_1111_1111_1111".to_string(),
+            oct: "0o37777777777".to_string(),
+            dec: "4294967295".to_string(),
+            hex: "0xFFFFFFFF".to_string(),
+            bin_twos: "0b1111_1111_1111_1111_1111_1111_1111_1111".to_string(),
+            oct_twos: "0o37777777777".to_string(),
+            hex_twos: "0xFFFFFFFF".to_string(),
+        })
+    );
+    assert_eq!(
+        format_all_with(-1, &unsigned(Width::W32)).map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(-1)))
+    );
+    assert_eq!(
+        format_all_with(0x1_0000u32, &unsigned(Width::W16)).map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(0x1_0000u32)))
+    );
+}
+
+#[test]
+fn unsigned_format_radix() {
+    assert_eq!(
+        format_radix_with(
+            u128::MAX,
+            16,
+            &FormatOptions::default(),
+            &unsigned(Width::W128)
+        ),
+        Ok(format!("0x{}", "F".repeat(32)))
+    );
+}
@@ -0,0 +1,252 @@
+mod common;
+
+use calc_core::{
+    eval_in, eval_with, format_all_with, format_radix_with, BinaryOp, CalcError, Context,
+    ErrorKind, EvalOptions, FormatOptions, FormattedValue, Value, Width,
+};
+use common::overflow;
+
+fn opts(width: Width) -> EvalOptions {
+    EvalOptions {
+        width,
+        ..EvalOptions::default()
+    }
+}
+
+// 既定は 32-bit
+#[test]
+fn width_default_is_32_bit() {
+    assert_eq!(EvalOptions::default().width, Width::W32);
+    assert_eq!(
+        eval_with("2147483647 + 1", &EvalOptions::default()).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[2147483647, 1]))
+    );
+}
+
+#[test]
+fn width_bounds() {
+    assert_eq!(Width::W8.min_value(), -128);
+    assert_eq!(Width::W8.max_value(), 127);
+    assert_eq!(Width::W16.max_value(), 32767);
+    assert_eq!(Width::W64.min_value(), i128::from(i64::MIN));
+    assert_eq!(Width::W128.max_value(), i128::MAX);
+}
+
+// リテラルの範囲
+#[test]
+fn width_literal_limits() {
+    assert_eq!(eval_with("127", &opts(Width::W8)), Ok(Value::from(127)));
+    assert_eq!(eval_with("-128", &opts(Width::W8)), Ok(Value::from(-128)));
+    assert_eq!(
+        eval_with("128", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        eval_with("-129", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        eval_with("-128 - 1", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Sub, &[-128, 1]))
+    );
+    assert_eq!(
+        eval_with("0x7FFF", &opts(Width::W16)),
+        Ok(Value::from(0x7FFF))
+    );
+    assert_eq!(
+        eval_with("0x8000", &opts(Width::W16)).map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+}
+
+#[test]
+fn width_64_bit_literals() {
+    assert_eq!(
+        eval_with("0x7FFF_FFFF_FFFF_FFFF", &opts(Width::W64)),
+        Ok(Value::from(i64::MAX))
+    );
+    assert_eq!(
+        eval_with("-9223372036854775808", &opts(Width::W64)),
+        Ok(Value::from(i64::MIN))
+    );
+}
+
+#[test]
+fn width_128_bit_extremes() {
+    assert_eq!(
+        eval_with(
+            "-170141183460469231731687303715884105728",
+            &opts(Width::W128)
+        ),
+        Ok(Value::from(i128::MIN))
+    );
+    assert_eq!(
+        eval_with(
+            "170141183460469231731687303715884105728",
+            &opts(Width::W128)
+        )
+        .map_err(CalcError::into_kind),
+        Err(ErrorKind::LiteralOutOfRange)
+    );
+    assert_eq!(
+        eval_with("2**126 * 2", &opts(Width::W128)).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Mul, &[1 << 126, 2]))
+    );
+    assert_eq!(
+        eval_with("-(2**126) * 2", &opts(Width::W128)),
+        Ok(Value::from(i128::MIN))
+    );
+}
+
+// 演算結果の範囲
+#[test]
+fn width_overflow_checks() {
+    assert_eq!(
+        eval_with("100 + 27", &opts(Width::W8)),
+        Ok(Value::from(127))
+    );
+    assert_eq!(
+        eval_with("100 + 28", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Add, &[100, 28]))
+    );
+    assert_eq!(
+        eval_with("-128 / -1", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Div, &[-128, -1]))
+    );
+    assert_eq!(
+        eval_with("2**31", &opts(Width::W64)),
+        Ok(Value::from(1i64 << 31))
+    );
+    assert_eq!(
+        eval_with("2**63", &opts(Width::W64)).map_err(CalcError::into_kind),
+        Err(overflow(BinaryOp::Pow, &[2, 63]))
+    );
+}
+
+// ビット操作は指定幅で行う
+#[test]
+fn width_shifts() {
+    assert_eq!(eval_with("1 << 6", &opts(Width::W8)), Ok(Value::from(64)));
+    assert_eq!(
+        eval_with("1 << 7", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::ShiftOverflow)
+    );
+    assert_eq!(
+        eval_with("1 << 8", &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::InvalidShiftAmount)
+    );
+    assert_eq!(
+        eval_with("-1 >>> 4", &opts(Width::W8)),
+        Ok(Value::from(0x0F))
+    );
+    assert_eq!(
+        eval_with("1 << 40", &opts(Width::W64)),
+        Ok(Value::from(1i64 << 40))
+    );
+    assert_eq!(
+        eval_with("1 << 127", &opts(Width::W128)).map_err(CalcError::into_kind),
+        Err(ErrorKind::ShiftOverflow)
+    );
+    assert_eq!(
+        eval_with("-1 << 127", &opts(Width::W128)),
+        Ok(Value::from(i128::MIN))
+    );
+}
+
+#[test]
+fn width_rotates() {
+    assert_eq!(
+        eval_with("0x12 rotl 4", &opts(Width::W8)),
+        Ok(Value::from(0x21))
+    );
+    assert_eq!(
+        eval_with("1 rotr 1", &opts(Width::W16)),
+        Ok(Value::from(-32768))
+    );
+    assert_eq!(
+        eval_with("1 rotr 1", &opts(Width::W128)),
+        Ok(Value::from(i128::MIN))
+    );
+}
+
+#[test]
+fn width_builtins() {
+    assert_eq!(
+        eval_with("popcount(-1)", &opts(Width::W8)),
+        Ok(Value::from(8))
+    );
+    assert_eq!(eval_with("clz(1)", &opts(Width::W64)), Ok(Value::from(63)));
+    assert_eq!(eval_with("ctz(0)", &opts(Width::W16)), Ok(Value::from(16)));
+    assert_eq!(
+        eval_with("bswap(0x1234)", &opts(Width::W16)),
+        Ok(Value::from(0x3412))
+    );
+    assert_eq!(
+        eval_with("bswap(0x12)", &opts(Width::W8)),
+        Ok(Value::from(0x12))
+    );
+    assert_eq!(
+        eval_with("bitrev(1)", &opts(Width::W8)),
+        Ok(Value::from(-128))
+    );
+    assert_eq!(
+        eval_with("popcount(-1)", &opts(Width::W128)),
+        Ok(Value::from(128))
+    );
+}
+
+// Context の幅
+#[test]
+fn width_context_options() {
+    let mut ctx = Context::with_options(opts(Width::W64));
+    assert_eq!(ctx.options().width, Width::W64);
+    eval_in(&mut ctx, "big = 1 << 40").unwrap();
+    assert_eq!(
+        eval_in(&mut ctx, "big | 1"),
+        Ok(Value::from((1i64 << 40) | 1))
+    );
+}
+
+#[test]
+fn width_context_rejects_out_of_range_variable() {
+    let mut ctx = Context::with_options(opts(Width::W8));
+    ctx.set("x", 1000);
+    assert_eq!(
+        eval_in(&mut ctx, "x").map_err(CalcError::into_kind),
+        Err(ErrorKind::VariableOutOfRange("x".to_string()))
+    );
+}
+
+// 出力
+#[test]
+fn width_format_all() {
+    assert_eq!(
+        format_all_with(i64::MIN, &opts(Width::W64)),
+        Ok(FormattedValue {
+            bin: format!("-0b1000{}", "_0000".repeat(15)),
+            oct: "-0o1000000000000000000000".to_string(),
+            dec: "-9223372036854775808".to_string(),
+            hex: "-0x8000000000000000".to_string(),
+            bin_twos: format!("0b1000{}", "_0000".repeat(15)),
+            oct_twos: "0o1000000000000000000000".to_string(),
+            hex_twos: "0x8000000000000000".to_string(),
+        })
+    );
+    assert_eq!(
+        format_all_with(128, &opts(Width::W8)).map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(128)))
+    );
+}
+
+#[test]
+fn width_format_radix() {
+    assert_eq!(
+        format_radix_with(i128::MAX, 36, &FormatOptions::default(), &opts(Width::W128)),
+        Ok("36r7KSYYIZZKUTUDZBV8AQZTECJJ".to_string())
+    );
+    assert_eq!(
+        format_radix_with(1i64 << 40, 16, &FormatOptions::default(), &opts(Width::W32))
+            .map_err(CalcError::into_kind),
+        Err(ErrorKind::ResultOutOfRange(Value::from(1i64 << 40)))
+    );
+}
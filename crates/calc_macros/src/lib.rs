@@ -0,0 +1,189 @@
+use calc_core::{eval_with, EvalOptions, Signedness, Value, Width};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+const USAGE: &str = "expected `calc!(\"expression\")` or `calc!(u32, \"expression\")`";
+
+/// `calc!("式")` は既定の設定（32-bit 符号付き）で評価して型を決めない整数リテラルに、
+/// `calc!(u32, "式")` はその型の幅・符号で評価して型付きのリテラルに展開する
+///
+/// ```
+/// const BYTE: u8 = calc_macros::calc!(u8, "0xFF");
+/// ```
+///
+/// 評価できない式はリテラルの位置でコンパイルエラーになる
+///
+/// ```compile_fail
+/// const BYTE: u8 = calc_macros::calc!(u8, "0xFF + 1");
+/// ```
+#[proc_macro]
+pub fn calc(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err((message, span)) => compile_error(&message, span),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, (String, Span)> {
+    let tokens: Vec<TokenTree> = input.into_iter().map(flatten).collect();
+    let (ty, literal) = match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => (None, literal),
+        [TokenTree::Ident(ty), TokenTree::Punct(comma), TokenTree::Literal(literal)]
+            if comma.as_char() == ',' =>
+        {
+            (Some(ty), literal)
+        }
+        _ => {
+            let span = tokens.first().map_or_else(Span::call_site, TokenTree::span);
+            return Err((USAGE.to_string(), span));
+        }
+    };
+
+    let options = match ty {
+        Some(ty) => type_options(ty)?,
+        None => EvalOptions::default(),
+    };
+    let span = literal.span();
+    let input = string_value(&literal.to_string()).ok_or_else(|| (USAGE.to_string(), span))?;
+    let value = eval_with(&input, &options).map_err(|err| (err.render(&input), span))?;
+    Ok(integer(&value, ty.map(Ident::to_string), span))
+}
+
+// macro_rules! から渡されたリテラルは区切りなしのグループに包まれている
+fn flatten(token: TokenTree) -> TokenTree {
+    match token {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next()) {
+                (Some(token), None) => flatten(token),
+                _ => TokenTree::Group(group),
+            }
+        }
+        token => token,
+    }
+}
+
+fn type_options(ty: &Ident) -> Result<EvalOptions, (String, Span)> {
+    let name = ty.to_string();
+    let (signedness, bits) = if let Some(bits) = name.strip_prefix('i') {
+        (Signedness::Signed, bits)
+    } else if let Some(bits) = name.strip_prefix('u') {
+        (Signedness::Unsigned, bits)
+    } else {
+        (Signedness::Signed, "")
+    };
+    let width = match bits {
+        "8" => Width::W8,
+        "16" => Width::W16,
+        "32" => Width::W32,
+        "64" => Width::W64,
+        "128" => Width::W128,
+        _ => {
+            return Err((
+                format!("unsupported type `{name}`: expected one of i8..i128 or u8..u128"),
+                ty.span(),
+            ))
+        }
+    };
+    Ok(EvalOptions {
+        width,
+        signedness,
+        ..EvalOptions::default()
+    })
+}
+
+fn integer(value: &Value, suffix: Option<String>, span: Span) -> TokenStream {
+    let (negative, magnitude) = match value.as_i128() {
+        Some(value) => (value < 0, value.unsigned_abs()),
+        None => (false, value.as_u128().unwrap_or_default()),
+    };
+    let mut literal: Literal = format!("{magnitude}{}", suffix.unwrap_or_default())
+        .parse()
+        .expect("integer literal");
+    literal.set_span(span);
+    if !negative {
+        return TokenTree::Literal(literal).into();
+    }
+
+    // 後ろにメソッド呼び出しなどが続いても符号が外れないよう括弧で包む
+    let mut minus = Punct::new('-', Spacing::Alone);
+    minus.set_span(span);
+    let inner: TokenStream = [TokenTree::Punct(minus), TokenTree::Literal(literal)]
+        .into_iter()
+        .collect();
+    let mut group = Group::new(Delimiter::Parenthesis, inner);
+    group.set_span(span);
+    TokenTree::Group(group).into()
+}
+
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    args.set_span(span);
+    let tokens = [
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("core", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(args),
+    ];
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+// 通常の文字列リテラルと raw 文字列リテラルの中身を取り出す
+fn string_value(text: &str) -> Option<String> {
+    if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw.get(hashes..raw.len().checked_sub(hashes)?)?;
+        return Some(body.strip_prefix('"')?.strip_suffix('"')?.to_string());
+    }
+    unescape(text.strip_prefix('"')?.strip_suffix('"')?)
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        let escaped = match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                char::from(u8::from_str_radix(&digits, 16).ok()?)
+            }
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let (digits, tail) = rest.split_once('}')?;
+                chars = tail.chars();
+                let digits = digits.replace('_', "");
+                char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?
+            }
+            // 行末の \ は次の行の先頭の空白ごと読み飛ばす
+            '\n' | '\r' => {
+                chars = chars.as_str().trim_start().chars();
+                continue;
+            }
+            _ => return None,
+        };
+        out.push(escaped);
+    }
+    Some(out)
+}
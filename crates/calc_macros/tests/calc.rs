@@ -0,0 +1,56 @@
+use calc_macros::calc;
+
+const MASK: u32 = calc!("0xFF << 8 | 0b1010");
+const LIMIT: u64 = calc!(u64, "1 << 40");
+
+// 型を指定しないリテラルは使う側の型になる
+#[test]
+fn constants() {
+    assert_eq!(MASK, 0xFF0A);
+    assert_eq!(LIMIT, 1 << 40);
+    let byte: u8 = calc!("popcount(0xFF) * 2");
+    assert_eq!(byte, 16);
+}
+
+// 型を指定するとその幅・符号で評価する
+#[test]
+fn typed() {
+    assert_eq!(calc!(u32, "0xFFFF_FFFF"), u32::MAX);
+    assert_eq!(calc!(i8, "-0x80"), i8::MIN);
+    assert_eq!(calc!(u8, "bitrev(1)"), 0x80);
+    assert_eq!(calc!(i128, "-(2 ** 126) * 2"), i128::MIN);
+    assert_eq!(calc!(u128, "~0"), u128::MAX);
+}
+
+// 負の値は括弧付きで展開される
+#[test]
+fn negative_values() {
+    assert_eq!(calc!("-7 / 2"), -4);
+    assert_eq!(calc!(i32, "-3").pow(2), 9);
+    assert_eq!(2 - calc!("-3"), 5);
+}
+
+#[test]
+fn string_forms() {
+    assert_eq!(calc!(r#"b"AB""#), 0x4142);
+    assert_eq!(calc!("'\\n' + 1"), 11);
+    assert_eq!(calc!("'\x41'"), 65);
+    assert_eq!(
+        calc!(
+            "0x10 \
+             + 1"
+        ),
+        17
+    );
+}
+
+macro_rules! forwarded {
+    ($input:literal) => {
+        calc!(u16, $input)
+    };
+}
+
+#[test]
+fn through_macro_rules() {
+    assert_eq!(forwarded!("0xF0 | 0x0F"), 0xFF_u16);
+}
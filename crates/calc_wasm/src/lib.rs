@@ -1,9 +1,15 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{window, Element, HtmlButtonElement, HtmlInputElement, KeyboardEvent};
+use web_sys::{
+    window, Document, Element, HtmlButtonElement, HtmlDetailsElement, HtmlInputElement,
+    KeyboardEvent,
+};
 
-use calc_core::{eval_expression, format_all};
+use calc_core::{
+    eval_in, eval_in_traced, eval_traced_in, format_all_with, Context, Span, TraceStep,
+};
 
 #[wasm_bindgen(start)]
 pub fn start() -> Result<(), JsValue> {
@@ -22,38 +28,98 @@ pub fn start() -> Result<(), JsValue> {
     let out_bin = document
         .get_element_by_id("out-bin")
         .ok_or_else(|| JsValue::from_str("missing #out-bin"))?;
+    let out_oct = document
+        .get_element_by_id("out-oct")
+        .ok_or_else(|| JsValue::from_str("missing #out-oct"))?;
     let out_dec = document
         .get_element_by_id("out-dec")
         .ok_or_else(|| JsValue::from_str("missing #out-dec"))?;
     let out_hex = document
         .get_element_by_id("out-hex")
         .ok_or_else(|| JsValue::from_str("missing #out-hex"))?;
+    let out_bin_twos = document
+        .get_element_by_id("out-bin-twos")
+        .ok_or_else(|| JsValue::from_str("missing #out-bin-twos"))?;
+    let out_oct_twos = document
+        .get_element_by_id("out-oct-twos")
+        .ok_or_else(|| JsValue::from_str("missing #out-oct-twos"))?;
+    let out_hex_twos = document
+        .get_element_by_id("out-hex-twos")
+        .ok_or_else(|| JsValue::from_str("missing #out-hex-twos"))?;
     let out_error = document
         .get_element_by_id("out-error")
         .ok_or_else(|| JsValue::from_str("missing #out-error"))?;
+    let out_trace = document
+        .get_element_by_id("out-trace")
+        .ok_or_else(|| JsValue::from_str("missing #out-trace"))?
+        .dyn_into::<HtmlDetailsElement>()?;
+    let out_trace_steps = document
+        .get_element_by_id("out-trace-steps")
+        .ok_or_else(|| JsValue::from_str("missing #out-trace-steps"))?;
 
     let input = Rc::new(input);
     let out_bin = Rc::new(out_bin);
+    let out_oct = Rc::new(out_oct);
     let out_dec = Rc::new(out_dec);
     let out_hex = Rc::new(out_hex);
+    let out_bin_twos = Rc::new(out_bin_twos);
+    let out_oct_twos = Rc::new(out_oct_twos);
+    let out_hex_twos = Rc::new(out_hex_twos);
     let out_error = Rc::new(out_error);
+    let out_trace = Rc::new(out_trace);
+    let out_trace_steps = Rc::new(out_trace_steps);
+
+    let context = Rc::new(RefCell::new(Context::new()));
+    // 過程を閉じている間は記録せず、開いたときに評価前の文脈で求める
+    let untraced: Rc<RefCell<Option<(String, Context)>>> = Rc::new(RefCell::new(None));
 
     let input_for_eval = Rc::clone(&input);
+    let document_for_eval = document.clone();
+    let trace_for_eval = Rc::clone(&out_trace);
+    let steps_for_eval = Rc::clone(&out_trace_steps);
+    let untraced_for_eval = Rc::clone(&untraced);
     let eval_action = Rc::new(move || {
         let expr = input_for_eval.value();
-        let result = eval_expression(&expr).and_then(format_all);
+        let mut context = context.borrow_mut();
+        let before = context.clone();
+        let result = if trace_for_eval.open() {
+            eval_in_traced(&mut context, &expr).map(|(value, trace)| (value, Some(trace)))
+        } else {
+            eval_in(&mut context, &expr).map(|value| (value, None))
+        }
+        .and_then(|(value, trace)| Ok((format_all_with(value, context.options())?, trace)));
+        untraced_for_eval.replace(None);
         match result {
-            Ok(formatted) => {
+            Ok((formatted, trace)) => {
                 set_text(&out_bin, &formatted.bin);
+                set_text(&out_oct, &formatted.oct);
                 set_text(&out_dec, &formatted.dec);
                 set_text(&out_hex, &formatted.hex);
+                set_text(&out_bin_twos, &formatted.bin_twos);
+                set_text(&out_oct_twos, &formatted.oct_twos);
+                set_text(&out_hex_twos, &formatted.hex_twos);
                 set_text(&out_error, "");
+                match trace {
+                    Some(trace) => render_trace(&document_for_eval, &steps_for_eval, &expr, &trace),
+                    None => {
+                        render_trace(&document_for_eval, &steps_for_eval, &expr, &[]);
+                        untraced_for_eval.replace(Some((expr, before)));
+                    }
+                }
             }
             Err(err) => {
                 set_text(&out_bin, "—");
+                set_text(&out_oct, "—");
                 set_text(&out_dec, "—");
                 set_text(&out_hex, "—");
-                set_text(&out_error, &err.to_string());
+                set_text(&out_bin_twos, "—");
+                set_text(&out_oct_twos, "—");
+                set_text(&out_hex_twos, "—");
+                set_text(&out_error, &err.render(&expr));
+                render_trace(&document_for_eval, &steps_for_eval, &expr, &[]);
+                if let Some(span) = err.span() {
+                    select_span(&input_for_eval, &expr, span);
+                }
             }
         }
     });
@@ -74,9 +140,68 @@ pub fn start() -> Result<(), JsValue> {
     input.add_event_listener_with_callback("keydown", key_closure.as_ref().unchecked_ref())?;
     key_closure.forget();
 
+    let trace_for_toggle = Rc::clone(&out_trace);
+    let toggle_closure = Closure::wrap(Box::new(move || {
+        if !trace_for_toggle.open() {
+            return;
+        }
+        if let Some((expr, before)) = untraced.take() {
+            let trace = eval_traced_in(&before, &expr).unwrap_or_default();
+            render_trace(&document, &out_trace_steps, &expr, &trace);
+        }
+    }) as Box<dyn FnMut()>);
+    out_trace
+        .add_event_listener_with_callback("toggle", toggle_closure.as_ref().unchecked_ref())?;
+    toggle_closure.forget();
+
     Ok(())
 }
 
 fn set_text(element: &Element, text: &str) {
     element.set_text_content(Some(text));
 }
+
+fn render_trace(document: &Document, list: &Element, input: &str, steps: &[TraceStep]) {
+    list.set_text_content(None);
+    for step in steps {
+        let _ = append_step(document, list, input, step);
+    }
+}
+
+fn append_step(
+    document: &Document,
+    list: &Element,
+    input: &str,
+    step: &TraceStep,
+) -> Result<(), JsValue> {
+    let details = document.create_element("details")?;
+    let summary = document.create_element("summary")?;
+    summary.set_text_content(Some(&format!("{} = {}", step.text(input), step.result.dec)));
+    details.append_child(&summary)?;
+
+    let lines = [
+        format!("被演算子: {}", step.operands.join(", ")),
+        format!("BIN {}", step.result.bin),
+        format!("OCT {}", step.result.oct),
+        format!("DEC {}", step.result.dec),
+        format!("HEX {}", step.result.hex),
+    ];
+    for line in lines {
+        let row = document.create_element("p")?;
+        row.set_class_name("value");
+        row.set_text_content(Some(&line));
+        details.append_child(&row)?;
+    }
+
+    let item = document.create_element("li")?;
+    item.append_child(&details)?;
+    list.append_child(&item)?;
+    Ok(())
+}
+
+fn select_span(input: &HtmlInputElement, text: &str, span: Span) {
+    // 入力欄の選択位置は UTF-16 単位
+    let offset = |idx: usize| text.get(..idx).map_or(0, |s| s.encode_utf16().count()) as u32;
+    let _ = input.focus();
+    let _ = input.set_selection_range(offset(span.start), offset(span.end));
+}